@@ -0,0 +1,73 @@
+// animation.rs — 动图（GIF / APNG）全景的播放计时
+//
+// 帧在加载时已全部解码并处理成纹理尺寸（见 loader.rs 的 prepare_for_upload），
+// 这里只决定每一帧何时该换下一帧；上传由主循环调用 Renderer::update_panorama_frame 完成。
+
+use image::RgbaImage;
+use std::time::{Duration, Instant};
+
+pub struct AnimationPlayer {
+    frames: Vec<(RgbaImage, Duration)>,
+    index: usize,
+    playing: bool,
+    frame_started: Instant,
+}
+
+impl AnimationPlayer {
+    /// frames 的第一帧应已显示在屏幕上
+    pub fn new(frames: Vec<(RgbaImage, Duration)>) -> Self {
+        Self {
+            frames,
+            index: 0,
+            playing: true,
+            frame_started: Instant::now(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn toggle_playing(&mut self) {
+        self.playing = !self.playing;
+        // 从暂停处继续时，当前帧重新计时
+        self.frame_started = Instant::now();
+    }
+
+    /// 每帧调用：到了换帧时间时返回需要上传的新帧
+    pub fn tick(&mut self) -> Option<&RgbaImage> {
+        if !self.playing || self.frames.len() < 2 {
+            return None;
+        }
+
+        let mut elapsed = self.frame_started.elapsed();
+        if elapsed < self.frames[self.index].1 {
+            return None;
+        }
+        // 窗口长时间未刷新（如最小化）时不必逐圈追赶
+        let total: Duration = self.frames.iter().map(|(_, d)| *d).sum();
+        if elapsed > total {
+            elapsed = Duration::from_nanos((elapsed.as_nanos() % total.as_nanos()) as u64);
+        }
+        // 渲染较慢时一次跳过多帧，保持动画的整体速度
+        while elapsed >= self.frames[self.index].1 {
+            elapsed -= self.frames[self.index].1;
+            self.index = (self.index + 1) % self.frames.len();
+        }
+
+        self.frame_started = Instant::now() - elapsed;
+        Some(&self.frames[self.index].0)
+    }
+}
@@ -0,0 +1,2363 @@
+// app.rs — 独立程序：窗口、事件循环、菜单和状态栏
+//
+// 渲染本身在 renderer.rs；这里只负责把窗口事件翻译成视角操作并绘制 UI。
+
+use crate::animation::AnimationPlayer;
+use crate::archive::ARCHIVE_EXTENSIONS;
+use crate::config::{AngleUnit, Config, YawDisplayRange};
+use crate::cubemap;
+use crate::deeplink::{self, DeepLink};
+use crate::keybindings::{Action, Keybindings};
+use crate::loader::{ImageLoader, PanoramaImage, STDIN_PATH};
+use crate::markers::{self, Marker, MarkerTarget};
+use crate::metadata::ImageMetadata;
+use crate::mesh_renderer::RenderPath;
+use crate::motion::ReduceMotion;
+use crate::orientation::{self, DeviceOrientation};
+use crate::panorama::{ImageKind, PanoramaViewer3D, ProjectionMode};
+use crate::playlist::{Playlist, IMAGE_EXTENSIONS};
+use crate::projection;
+use crate::renderer::{FrameStats, Renderer};
+use crate::shader_dev;
+use crate::shortcuts::SHORTCUTS;
+use crate::sidecar::ViewSidecar;
+use crate::sun::SunPosition;
+use crate::toast::Toasts;
+
+use winit::{
+    dpi::{LogicalSize, PhysicalPosition},
+    event::*,
+    event_loop::{ControlFlow, EventLoop},
+    window::{Fullscreen, WindowBuilder},
+};
+
+use std::path::PathBuf;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 取景框菜单中可选的全画幅等效焦距（mm）
+const LENS_FRAME_PRESETS: [u32; 7] = [14, 24, 35, 50, 85, 135, 200];
+
+/// 纹理尺寸上限菜单中可选的边长；不低于 GPU 上限的项不显示
+const TEXTURE_LIMIT_PRESETS: [u32; 4] = [16384, 8192, 4096, 2048];
+
+/// 滚轮每格的 FOV 步长（度）：默认 / Ctrl 精细 / Shift 粗调
+const ZOOM_STEP: f32 = 2.5;
+const ZOOM_STEP_FINE: f32 = 0.5;
+const ZOOM_STEP_COARSE: f32 = 10.0;
+/// 滚轮加速：两格间隔短于这个时间视为连续滚动，每格倍率乘以 ZOOM_ACCEL_GROWTH，最多 ZOOM_ACCEL_MAX 倍
+const ZOOM_ACCEL_WINDOW: Duration = Duration::from_millis(120);
+const ZOOM_ACCEL_GROWTH: f32 = 1.35;
+const ZOOM_ACCEL_MAX: f32 = 6.0;
+/// 缩放按钮每次点击的 FOV 步长（度）：没有滚轮的设备靠它缩放，步子比滚轮一格大
+const ZOOM_BUTTON_STEP: f32 = 5.0;
+
+/// 滚轮加速的状态：记录上一格的时间、方向和当前倍率，停顿或反向后倍率回到 1
+struct WheelAcceleration {
+    last_tick: Option<(Instant, bool)>,
+    factor: f32,
+}
+
+impl WheelAcceleration {
+    fn new() -> Self {
+        Self { last_tick: None, factor: 1.0 }
+    }
+
+    /// 新的一格滚动（zoom_in 为方向）对应的步长倍率
+    fn tick(&mut self, now: Instant, zoom_in: bool) -> f32 {
+        let rapid = self
+            .last_tick
+            .is_some_and(|(t, dir)| dir == zoom_in && now.duration_since(t) < ZOOM_ACCEL_WINDOW);
+        self.factor = if rapid { (self.factor * ZOOM_ACCEL_GROWTH).min(ZOOM_ACCEL_MAX) } else { 1.0 };
+        self.last_tick = Some((now, zoom_in));
+        self.factor
+    }
+}
+
+/// 菜单发起的图片切换
+enum Navigation {
+    Open(PathBuf),
+    /// 立方体贴图：所选文件夹内的文件或直接选中的六个文件
+    OpenCubemap(Vec<PathBuf>),
+    /// 播放列表内前后移动（+1 下一张，-1 上一张）
+    Step(isize),
+}
+
+/// 最近一次加载的耗时，显示在状态栏
+#[derive(Clone, Copy)]
+struct LoadTiming {
+    decode: Duration,
+    /// 含后台处理与纹理上传的端到端耗时
+    total: Duration,
+}
+
+/// 已解码、正在上传纹理的图片：上传完成后才应用其元数据
+struct PendingLoad {
+    metadata: ImageMetadata,
+    decode_time: Duration,
+    worker_time: Duration,
+    upload_start: Instant,
+    kind: ImageKind,
+    auto_exposure_ev: f32,
+    view: Option<ViewSidecar>,
+}
+
+/// draw_ui 需要读写的界面状态
+struct UiState {
+    show_fps: bool,
+    vsync_enabled: bool,
+    fps: f32,
+    is_loading: bool,
+    last_load: Option<LoadTiming>,
+    current_lang: String,
+    show_sun: bool,
+    sun: Option<SunPosition>,
+    orientation_supported: bool,
+    use_orientation: bool,
+    /// 源图每度纹素数，以及它与屏幕中心每度像素数之比（< 1 表示正在放大，超出原图细节）
+    resolution: Option<(f32, f32)>,
+    /// 已上传源纹理的尺寸（受纹理尺寸上限影响），与 resolution 一起显示
+    texture_size: Option<(u32, u32)>,
+    /// GPU 支持的最大纹理边长，纹理尺寸上限菜单据此列出可选项
+    gpu_max_texture: u32,
+    toasts: Toasts,
+    config: Config,
+    /// 菜单中点了“复制当前视图”，渲染完成后处理
+    copy_view_requested: bool,
+    /// “导出旋转后的全景”窗口
+    show_export_rotation: bool,
+    /// 导出时的新正前方与 roll（度）
+    export_rotation: [f32; 3],
+    /// 窗口中选好了保存位置，渲染完成后交给后台线程处理
+    export_requested: Option<PathBuf>,
+    /// 后台导出的结果
+    export_rx: Option<Receiver<Result<PathBuf, String>>>,
+    /// 着色器编译 / GPU 错误信息，显示在单独的窗口中
+    shader_error: Option<String>,
+    /// 快捷键帮助窗口（F1）
+    show_shortcuts: bool,
+    /// 曝光补偿（EV），只对本次运行有效
+    exposure_ev: f32,
+    /// 当前图片的自动曝光建议值
+    auto_exposure_ev: Option<f32>,
+    /// 启动时由 Config::keybindings 生成
+    keybindings: Keybindings,
+    /// 取色器：状态栏显示鼠标下源图像素的颜色
+    show_eyedropper: bool,
+    eyedropper: Option<[u8; 4]>,
+    /// 期望的渲染方式；切换失败时由主循环改回 renderer 的实际值
+    render_path: RenderPath,
+    /// 当前图片是动图时的播放状态
+    animation: Option<AnimationPlayer>,
+    /// 当前图片的类型；用户切换投影时记到这一类下
+    image_kind: Option<ImageKind>,
+    /// 命令行指定了投影：启动后的第一张图片不按类型自动切换
+    cli_projection: bool,
+    /// 命令行指定了视角（--yaw 等）：第一张图片不应用视角文件
+    cli_view: bool,
+    /// 启动时的深链接带有水平校正与曝光：第一张图片不清除校正，也不应用自动曝光
+    cli_adjustments: bool,
+    show_markers: bool,
+    /// 标注菜单中正在输入的新标注文字
+    marker_text: String,
+    /// A/B 分屏对比：右半边的投影（左半边为当前投影），None 为关闭
+    split_mode: Option<ProjectionMode>,
+    /// 诊断窗口：交换链呈现模式与上一帧的统计（每帧从 renderer 复制）
+    show_diagnostics: bool,
+    /// FOV 范围窗口（按投影修改缩放范围）
+    show_fov_limits: bool,
+    present_mode: wgpu::PresentMode,
+    frame_stats: FrameStats,
+}
+
+/// 创建窗口并运行事件循环（独立程序的全部逻辑）
+pub fn run() {
+    // i18n
+    let current_lang = crate::i18n::resolve_lang_from_args();
+    crate::i18n::init(current_lang.clone());
+    crate::cli::handle_info_flags();
+    crate::logging::init();
+
+    let event_loop = EventLoop::new();
+    let window = Arc::new(
+        WindowBuilder::new()
+            .with_title(crate::i18n::tr("app.title"))
+            .with_inner_size(LogicalSize::new(1280, 720))
+            .build(&event_loop)
+            .unwrap(),
+    );
+
+    // Renderer 初始化不再需要 Mesh，改用全屏 Ray Casting
+    let mut renderer = pollster::block_on(Renderer::new(window.clone()));
+    if let Some(path) = shader_dev::path_from_args() {
+        renderer.enable_shader_hot_reload(path);
+    }
+    if let Some(frames) = crate::bench::frames_from_args() {
+        crate::bench::run(&mut renderer, frames);
+        return;
+    }
+    let config = Config::load();
+    let mut viewer = PanoramaViewer3D::new();
+    // 自定义的 FOV 范围要在命令行参数之前设置，--fov 按它夹取
+    viewer.fov_limits = config.fov_limits.clone();
+    // 深链接先应用，单独的 --yaw 等参数可以再覆盖其中的字段
+    let deep_link = crate::cli::value("--open").map(|link| viewer.from_query(&link));
+    let cli_projection = viewer.apply_cli_args() || deep_link.is_some();
+
+    // 交互状态
+    let mut modifiers = ModifiersState::empty();
+    let mut mouse_pressed = false;
+    // 窗口最小化（尺寸为 0 或系统报告已最小化）时跳过渲染
+    let mut minimized = is_minimized(&window);
+    // 平面展开模式下按住中键拖动平移图片
+    let mut middle_pressed = false;
+    let mut last_mouse_pos: Option<PhysicalPosition<f64>> = None;
+    let mut wheel_acceleration = WheelAcceleration::new();
+
+    // FPS 计算
+    let mut last_frame_time = Instant::now();
+    let mut frame_count = 0;
+    let mut last_redraw = Instant::now();
+
+    // UI 状态
+    let mut ui_state = UiState {
+        show_fps: false,
+        vsync_enabled: true,
+        fps: 0.0,
+        is_loading: false,
+        last_load: None,
+        current_lang,
+        show_sun: true,
+        sun: None,
+        orientation_supported: orientation::is_supported(),
+        use_orientation: false,
+        resolution: None,
+        texture_size: None,
+        gpu_max_texture: renderer.gpu_max_texture_dimension(),
+        toasts: Toasts::default(),
+        keybindings: Keybindings::new(&config.keybindings),
+        config,
+        copy_view_requested: false,
+        show_export_rotation: false,
+        export_rotation: [0.0; 3],
+        export_requested: None,
+        export_rx: None,
+        shader_error: None,
+        show_shortcuts: false,
+        exposure_ev: deep_link.as_ref().map_or(0.0, |link| link.exposure_ev),
+        auto_exposure_ev: None,
+        show_eyedropper: false,
+        eyedropper: None,
+        render_path: RenderPath::from_args().unwrap_or_default(),
+        animation: None,
+        image_kind: None,
+        cli_projection,
+        cli_view: ["--projection", "--fov", "--yaw", "--pitch", "--open"]
+            .iter()
+            .any(|f| crate::cli::value(f).is_some()),
+        cli_adjustments: deep_link.is_some(),
+        show_markers: true,
+        marker_text: String::new(),
+        split_mode: None,
+        show_diagnostics: false,
+        show_fov_limits: false,
+        present_mode: renderer.present_mode(),
+        frame_stats: FrameStats::default(),
+    };
+    // 链接中的暗角 / 接缝羽化覆盖配置中的值（不立即写回配置文件），范围与菜单中的滑块相同
+    if let Some(link) = &deep_link {
+        if let Some(strength) = link.vignette {
+            ui_state.config.vignette_strength = strength.clamp(0.0, 1.0);
+        }
+        if let Some(deg) = link.seam_blend_deg {
+            ui_state.config.seam_blend_deg = deg.clamp(0.0, 10.0);
+        }
+    }
+
+    // 剪贴板需要一直持有：部分平台上 Clipboard 被释放后内容随之消失
+    let mut clipboard: Option<arboard::Clipboard> = None;
+
+    // 设备姿态传感器（菜单打开时才启动）
+    let mut device_orientation: Option<DeviceOrientation> = None;
+
+    // 后台加载 + 预取
+    let mut loader = ImageLoader::new();
+    renderer.set_texture_limit(ui_state.config.max_texture_size);
+    loader.set_max_texture_dimension(renderer.max_texture_dimension());
+    if let Some(megapixels) = crate::cli::number("--max-megapixels") {
+        loader.set_max_pixels((megapixels.max(1.0) as f64 * 1e6) as u64);
+    }
+    let mut pending_load: Option<PendingLoad> = None;
+    // 命令行中的图片路径优先于深链接中的
+    match crate::cli::path_from_args().or_else(|| deep_link.and_then(|link| link.image)) {
+        Some(path) if path == STDIN_PATH => {
+            ui_state.is_loading = true;
+            loader.open_stdin();
+        }
+        Some(path) => {
+            ui_state.is_loading = true;
+            loader.open(PathBuf::from(path));
+        }
+        // 按设置重新打开上次的图片（后台加载，视角文件照常生效）；文件已不存在时跳过
+        None => match ui_state.config.last_image.clone().filter(|_| ui_state.config.reopen_last_image) {
+            Some(path) if path.is_file() => {
+                ui_state.is_loading = true;
+                loader.open(path);
+            }
+            Some(path) => log::info!(
+                "{}",
+                crate::i18n::tr_with("log.last_image_missing", &[("path", path.display().to_string())])
+            ),
+            None => {}
+        },
+    }
+
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = ControlFlow::Poll;
+
+        // 检查是否有新加载的图片
+        // 等矩形图片分帧上传：期间继续显示旧图，上传完成后才应用新图片的元数据
+        let loaded = match loader.poll() {
+            Some(Ok(loaded)) => Some(loaded),
+            // 加载失败（如图片过大）：结束“加载中”并提示，继续显示当前图片
+            Some(Err(e)) => {
+                ui_state.is_loading = false;
+                ui_state.toasts.error(e);
+                None
+            }
+            None => None,
+        };
+        if let Some(loaded) = loaded {
+            ui_state.animation = None;
+            remember_last_image(&loader, &mut ui_state);
+            let pending = PendingLoad {
+                metadata: loaded.metadata,
+                decode_time: loaded.decode_time,
+                worker_time: loaded.worker_time,
+                upload_start: Instant::now(),
+                kind: loaded.kind,
+                auto_exposure_ev: loaded.auto_exposure_ev,
+                view: loaded.view,
+            };
+            match loaded.image {
+                PanoramaImage::Equirect(rgba) => {
+                    renderer.begin_prepared_upload(rgba, loaded.full_resolution, loaded.coverage);
+                    pending_load = Some(pending);
+                }
+                PanoramaImage::Cubemap(faces) => {
+                    renderer.load_cubemap(*faces);
+                    pending_load = None;
+                    finish_load(pending, &renderer, &mut viewer, &mut ui_state);
+                }
+                PanoramaImage::Animated(frames) => {
+                    if let Some((first, _)) = frames.first() {
+                        renderer.load_prepared_panorama(first, loaded.coverage);
+                    }
+                    ui_state.animation = Some(AnimationPlayer::new(frames));
+                    pending_load = None;
+                    finish_load(pending, &renderer, &mut viewer, &mut ui_state);
+                }
+            }
+        }
+
+        match event {
+            Event::WindowEvent { event, .. } => {
+                // 先让 egui 处理事件
+                let response = renderer.egui_state.on_event(&renderer.egui_ctx, &event);
+                if response.consumed {
+                    // egui 中途接管指针（拖到界面上、在菜单上松开按键）时结束场景拖动：
+                    // 否则 mouse_pressed 停在 true、last_mouse_pos 过期，下一次移动视角会跳变
+                    if matches!(event, WindowEvent::CursorMoved { .. } | WindowEvent::MouseInput { .. }) {
+                        mouse_pressed = false;
+                        middle_pressed = false;
+                        last_mouse_pos = None;
+                    }
+                    return;
+                }
+
+                match event {
+                    WindowEvent::CloseRequested => {
+                        *control_flow = ControlFlow::Exit;
+                    }
+
+                    WindowEvent::ModifiersChanged(state) => {
+                        modifiers = state;
+                    }
+
+                    WindowEvent::Resized(new_size) => {
+                        renderer.resize(new_size);
+                        minimized = is_minimized(&window);
+                    }
+
+                    WindowEvent::Focused(focused) => {
+                        minimized = is_minimized(&window);
+                        // 失去焦点后可能收不到松开按键的事件
+                        if !focused {
+                            mouse_pressed = false;
+                            middle_pressed = false;
+                            last_mouse_pos = None;
+                        }
+                    }
+
+                    // 键盘快捷键
+                    WindowEvent::KeyboardInput { input, .. }
+                        if input.state == ElementState::Pressed =>
+                    {
+                        match input.virtual_keycode.and_then(|key| ui_state.keybindings.action(key)) {
+                            Some(Action::Open) => {
+                                if let Some(path) = pick_panorama_file() {
+                                    ui_state.is_loading = true;
+                                    loader.open(path);
+                                }
+                            }
+                            // 跳转到正北/东/南/西（相对 yaw_offset），回到水平线
+                            Some(Action::North) => {
+                                viewer.snap_to_heading(0.0);
+                            }
+                            Some(Action::East) => {
+                                viewer.snap_to_heading(90.0);
+                            }
+                            Some(Action::South) => {
+                                viewer.snap_to_heading(180.0);
+                            }
+                            Some(Action::West) => {
+                                viewer.snap_to_heading(270.0);
+                            }
+                            Some(Action::LevelHorizon) => {
+                                viewer.level_horizon();
+                            }
+                            Some(Action::Reset) => {
+                                viewer.reset_view();
+                            }
+                            Some(Action::Fit) => {
+                                viewer.fit_to_extents();
+                            }
+                            // 按常用焦距缩放
+                            Some(Action::FocalShorter) => {
+                                viewer.step_focal_preset(-1);
+                            }
+                            Some(Action::FocalLonger) => {
+                                viewer.step_focal_preset(1);
+                            }
+                            // 循环切换投影模式，按住 Shift 反向
+                            Some(Action::CycleProjection) if ui_state.render_path == RenderPath::RayCast => {
+                                let delta = if modifiers.shift() { -1 } else { 1 };
+                                let mode = viewer.projection_mode.cycle(delta);
+                                choose_projection(&mut viewer, &mut ui_state, mode);
+                            }
+                            // 动图：播放 / 暂停
+                            Some(Action::ToggleAnimation) => {
+                                if let Some(animation) = ui_state.animation.as_mut() {
+                                    animation.toggle_playing();
+                                }
+                            }
+                            Some(Action::Next) => {
+                                ui_state.is_loading |= loader.step(1);
+                            }
+                            Some(Action::Previous) => {
+                                ui_state.is_loading |= loader.step(-1);
+                            }
+                            Some(Action::CopyView) if modifiers.ctrl() || modifiers.logo() => {
+                                copy_view_to_clipboard(&renderer, &mut clipboard, &mut ui_state.toasts);
+                            }
+                            Some(Action::Help) => {
+                                ui_state.show_shortcuts = !ui_state.show_shortcuts;
+                            }
+                            Some(Action::Fullscreen) => {
+                                let on = !viewer.is_fullscreen;
+                                set_fullscreen(&window, &mut viewer, &ui_state.config, on);
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    // 鼠标交互
+                    WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
+                        mouse_pressed = state == ElementState::Pressed;
+                        if !mouse_pressed {
+                            last_mouse_pos = None;
+                        }
+                    }
+
+                    WindowEvent::MouseInput { state, button: MouseButton::Middle, .. } => {
+                        middle_pressed = state == ElementState::Pressed;
+                        if !middle_pressed {
+                            last_mouse_pos = None;
+                        }
+                    }
+
+                    // 中键平移：图片跟随指针 1:1 移动（不受灵敏度影响），与常见看图软件一致；
+                    // 左键拖动仍和 3D 模式一样改变视线方向
+                    WindowEvent::CursorMoved { position, .. }
+                        if middle_pressed
+                            && !mouse_pressed
+                            && viewer.projection_mode == ProjectionMode::Equirectangular =>
+                    {
+                        viewer.stop_animation();
+                        if let Some(last_pos) = last_mouse_pos {
+                            let height = renderer.size.height as f32;
+                            if height > 0.0 {
+                                let deg_per_px = viewer.fov / height;
+                                viewer.pan_by(
+                                    -(position.x - last_pos.x) as f32 * deg_per_px,
+                                    -(position.y - last_pos.y) as f32 * deg_per_px,
+                                );
+                            }
+                        }
+                        last_mouse_pos = Some(position);
+                    }
+
+                    WindowEvent::CursorMoved { position, .. } if mouse_pressed => {
+                        viewer.stop_animation();
+                        if let Some(last_pos) = last_mouse_pos {
+                            let dx = (position.x - last_pos.x) as f32;
+                            let dy = (position.y - last_pos.y) as f32;
+
+                            let width = renderer.size.width as f32;
+                            let height = renderer.size.height as f32;
+
+                            if width > 0.0 && height > 0.0 {
+                                let (yaw_per_px_deg, pitch_per_px_deg) =
+                                    if viewer.projection_mode == ProjectionMode::Equirectangular {
+                                        // 平面展开：两个方向都是线性的，一度对应相同像素数
+                                        let deg = viewer.fov / height;
+                                        (deg, deg)
+                                    } else {
+                                        let v_f = viewer.fov.to_radians();
+                                        let aspect = width / height;
+                                        let h_f = 2.0 * ((v_f / 2.0).tan() * aspect).atan();
+                                        ((h_f / width).to_degrees(), (v_f / height).to_degrees())
+                                    };
+
+                                let yaw_sign = if viewer.is_upside_down() { -1.0 } else { 1.0 };
+                                let yaw = viewer.yaw
+                                    - yaw_sign * dx * yaw_per_px_deg * viewer.sensitivity_scale;
+                                let pitch = viewer.constrain_pitch(
+                                    viewer.pitch - dy * pitch_per_px_deg * viewer.sensitivity_scale,
+                                );
+                                // 局部全景：拖出有效内容时加阻尼，松开后回弹
+                                (viewer.yaw, viewer.pitch) = viewer.resist_bounds(
+                                    (viewer.yaw, viewer.pitch),
+                                    (yaw, pitch),
+                                    width / height,
+                                );
+                            }
+                        }
+                        last_mouse_pos = Some(position);
+                    }
+
+                    // 指针离开窗口：重新进入时从新位置开始计算位移，视角不会跳到指针处
+                    WindowEvent::CursorLeft { .. } => {
+                        last_mouse_pos = None;
+                    }
+
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let ((x, y), notched) = match delta {
+                            MouseScrollDelta::LineDelta(x, y) => ((x, y), true),
+                            MouseScrollDelta::PixelDelta(pos) => ((pos.x as f32 / 20.0, pos.y as f32 / 20.0), false),
+                        };
+                        // 部分平台按住 Shift 时把竖直滚动转换为水平滚动
+                        let scroll = if y == 0.0 { x } else { y };
+                        let step = if modifiers.ctrl() {
+                            ZOOM_STEP_FINE
+                        } else if modifiers.shift() {
+                            ZOOM_STEP_COARSE
+                        } else {
+                            ZOOM_STEP
+                        };
+                        // 加速只用于一格一格的滚轮：触控板的像素滚动事件本来就很密集；Ctrl 精细调整时也不加速
+                        let settings = ui_state.config.scroll_zoom;
+                        let factor = if settings.acceleration && notched && !modifiers.ctrl() {
+                            wheel_acceleration.tick(Instant::now(), scroll > 0.0)
+                        } else {
+                            1.0
+                        };
+
+                        viewer.zoom_by(scroll * step * settings.sensitivity * factor);
+                    }
+
+                    WindowEvent::DroppedFile(path) => {
+                        ui_state.is_loading = true;
+                        loader.open(path);
+                    }
+
+                    _ => {}
+                }
+            }
+
+            // 最小化期间不渲染（部分平台仍会发来重绘请求）；系统要求重绘时顺便确认是否已经恢复
+            Event::RedrawRequested(_) if minimized => {
+                minimized = is_minimized(&window);
+            }
+
+            Event::RedrawRequested(_) => {
+                // FPS 统计
+                frame_count += 1;
+                let now = Instant::now();
+                if now.duration_since(last_frame_time).as_secs_f32() >= 1.0 {
+                    ui_state.fps =
+                        frame_count as f32 / now.duration_since(last_frame_time).as_secs_f32();
+                    frame_count = 0;
+                    last_frame_time = now;
+                }
+
+                // 减少动态效果：视角动画与界面动画共用这一个开关
+                viewer.reduce_motion = ui_state.config.reduce_motion.enabled();
+                let animation_time =
+                    if viewer.reduce_motion { 0.0 } else { egui::Style::default().animation_time };
+                if renderer.egui_ctx.style().animation_time != animation_time {
+                    renderer.egui_ctx.style_mut(|style| style.animation_time = animation_time);
+                }
+
+                if renderer.continue_upload() {
+                    if let Some(pending) = pending_load.take() {
+                        finish_load(pending, &renderer, &mut viewer, &mut ui_state);
+                    }
+                }
+
+                viewer.update_animation();
+
+                // 设备姿态：叠加传感器的变化量，鼠标仍可随时调整
+                if !ui_state.use_orientation {
+                    device_orientation = None;
+                } else if device_orientation.is_none() {
+                    device_orientation = DeviceOrientation::start();
+                    ui_state.use_orientation = device_orientation.is_some();
+                }
+                if let Some((dyaw, dpitch)) =
+                    device_orientation.as_mut().and_then(|d| d.take_delta())
+                {
+                    viewer.yaw += dyaw;
+                    viewer.pitch += dpitch;
+                }
+
+                // 限位设置、缩放或模式切换都可能让当前 pitch 越界，统一在这里约束
+                viewer.pitch = viewer.constrain_pitch(viewer.pitch);
+
+                let dt = now.duration_since(last_redraw).as_secs_f32();
+                last_redraw = now;
+                if !mouse_pressed {
+                    viewer.settle_into_bounds(renderer.aspect(), dt);
+                }
+
+                if ui_state.render_path != renderer.render_path() {
+                    match renderer.set_render_path(ui_state.render_path) {
+                        // 网格路径只有透视相机
+                        Ok(()) if ui_state.render_path == RenderPath::Mesh => {
+                            viewer.set_projection_mode(
+                                ProjectionMode::Rectilinear,
+                                ui_state.config.keep_fov_on_mode_switch,
+                            );
+                        }
+                        Ok(()) => {}
+                        Err(e) => {
+                            ui_state
+                                .toasts
+                                .error(crate::i18n::tr_with("error.mesh_renderer", &[("err", e)]));
+                            ui_state.render_path = renderer.render_path();
+                        }
+                    }
+                }
+
+                if let Some(frame) = ui_state.animation.as_mut().and_then(|a| a.tick()) {
+                    renderer.update_panorama_frame(frame);
+                }
+
+                renderer.poll_shader_reload();
+                renderer.poll_errors();
+                ui_state.shader_error = renderer.shader_error().map(str::to_owned);
+
+                // 更新相机矩阵和投影模式
+                renderer.set_seam_blend(ui_state.config.seam_blend_deg);
+                renderer.set_pan(viewer.pan);
+                renderer.set_alignment(viewer.alignment);
+                renderer.set_linear_source(ui_state.config.linear_source);
+                renderer.set_vignette(ui_state.config.vignette_strength);
+                renderer.set_load_fade(ui_state.config.fade_in && !viewer.reduce_motion);
+                renderer.set_exposure(ui_state.exposure_ev);
+                renderer.set_split_mode(ui_state.split_mode);
+                renderer.update_camera(viewer.yaw, viewer.pitch, viewer.fov, viewer.projection_mode);
+
+                ui_state.resolution = renderer.texels_per_degree().map(|texels| {
+                    let view = viewer.view_params(renderer.aspect());
+                    let screen_per_degree = projection::center_pixels_per_radian(
+                        &view,
+                        renderer.size.height as f32,
+                    )
+                    .to_radians();
+                    (texels, texels / screen_per_degree)
+                });
+                ui_state.texture_size = renderer.texture_size();
+
+                // 分屏时屏幕位置和视角不再一一对应，取色器暂停
+                ui_state.eyedropper = if ui_state.show_eyedropper && !split_active(&ui_state) {
+                    pick_source_color(&renderer, &viewer)
+                } else {
+                    None
+                };
+
+                if ui_state.show_diagnostics {
+                    ui_state.present_mode = renderer.present_mode();
+                    ui_state.frame_stats = renderer.frame_stats().clone();
+                }
+
+                // 渲染 UI 和 场景
+                let mut nav = None;
+                let aspect = renderer.aspect();
+                let playlist = loader.playlist();
+                let render_result = renderer.render_with_ui(&window, |ctx| {
+                    draw_ui(ctx, &mut viewer, &mut ui_state, &mut nav, playlist, aspect, &window);
+                });
+
+                if std::mem::take(&mut ui_state.copy_view_requested) {
+                    copy_view_to_clipboard(&renderer, &mut clipboard, &mut ui_state.toasts);
+                }
+                if let Some(path) = ui_state.export_requested.take() {
+                    if let Some(source) = renderer.source_image() {
+                        let strip = renderer.coverage().filter(|c| c.strip).map(|c| c.pitch);
+                        ui_state.export_rx =
+                            Some(spawn_export_rotated(source.clone(), strip, ui_state.export_rotation, path));
+                        ui_state.toasts.info(crate::i18n::tr("toast.export_started"));
+                    }
+                }
+                if let Some(result) = ui_state.export_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+                    ui_state.export_rx = None;
+                    match result {
+                        Ok(path) => ui_state.toasts.info(crate::i18n::tr_with(
+                            "toast.export_saved",
+                            &[("path", path.display().to_string())],
+                        )),
+                        Err(e) => ui_state.toasts.error(e),
+                    }
+                }
+
+                // 纹理尺寸上限可能刚在菜单中修改：先更新，重新打开的图片按新的上限缩放
+                renderer.set_texture_limit(ui_state.config.max_texture_size);
+                loader.set_max_texture_dimension(renderer.max_texture_dimension());
+
+                match nav {
+                    Some(Navigation::Open(path)) => {
+                        ui_state.is_loading = true;
+                        loader.open(path);
+                    }
+                    Some(Navigation::OpenCubemap(files)) => match cubemap::assign_faces(&files) {
+                        Ok(faces) => {
+                            ui_state.is_loading = true;
+                            loader.open_cubemap(faces);
+                        }
+                        Err(e) => log::error!("{}", e),
+                    },
+                    Some(Navigation::Step(delta)) => {
+                        ui_state.is_loading |= loader.step(delta);
+                    }
+                    None => {}
+                }
+
+                match render_result {
+                    Ok(_) => {}
+                    Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => renderer.resize(renderer.size),
+                    Err(wgpu::SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
+                    // 超时通常是窗口被遮挡 / 正在最小化，跳过这一帧即可
+                    Err(wgpu::SurfaceError::Timeout) => {}
+                }
+            }
+
+            // 最小化时停止主动重绘并休眠到下一个事件，恢复时的 Resized / Focused 会唤醒
+            Event::MainEventsCleared if minimized => {
+                *control_flow = ControlFlow::Wait;
+            }
+
+            Event::MainEventsCleared => {
+                window.request_redraw();
+            }
+
+            _ => {}
+        }
+    });
+}
+
+fn draw_ui(
+    ctx: &egui::Context,
+    viewer: &mut PanoramaViewer3D,
+    ui_state: &mut UiState,
+    nav: &mut Option<Navigation>,
+    playlist: Option<&Playlist>,
+    aspect: f32,
+    window: &winit::window::Window,
+) {
+    egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+        egui::menu::bar(ui, |ui| {
+            // File
+            ui.menu_button(crate::i18n::tr("menu.file"), |ui| {
+                if ui.button(crate::i18n::tr("menu.open_image")).clicked() {
+                    ui.close_menu();
+                    if let Some(path) = pick_panorama_file() {
+                        *nav = Some(Navigation::Open(path));
+                    }
+                }
+                ui.menu_button(crate::i18n::tr("menu.open_cubemap"), |ui| {
+                    if ui.button(crate::i18n::tr("menu.open_cubemap_folder")).clicked() {
+                        ui.close_menu();
+                        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                            *nav = Some(Navigation::OpenCubemap(cubemap::files_in_folder(&dir)));
+                        }
+                    }
+                    if ui.button(crate::i18n::tr("menu.open_cubemap_files")).clicked() {
+                        ui.close_menu();
+                        if let Some(files) = rfd::FileDialog::new()
+                            .add_filter(crate::i18n::tr("file.filter.images"), IMAGE_EXTENSIONS)
+                            .pick_files()
+                        {
+                            *nav = Some(Navigation::OpenCubemap(files));
+                        }
+                    }
+                });
+
+                let can_step = playlist.is_some_and(|p| p.len() > 1);
+                if ui
+                    .add_enabled(can_step, egui::Button::new(crate::i18n::tr("menu.previous_image")))
+                    .clicked()
+                {
+                    *nav = Some(Navigation::Step(-1));
+                    ui.close_menu();
+                }
+                if ui
+                    .add_enabled(can_step, egui::Button::new(crate::i18n::tr("menu.next_image")))
+                    .clicked()
+                {
+                    *nav = Some(Navigation::Step(1));
+                    ui.close_menu();
+                }
+
+                ui.separator();
+                if ui
+                    .checkbox(&mut ui_state.config.reopen_last_image, crate::i18n::tr("menu.reopen_last_image"))
+                    .on_hover_text(crate::i18n::tr("menu.reopen_last_image_hint"))
+                    .changed()
+                {
+                    save_config(ui_state);
+                }
+                ui.separator();
+                if ui.button(crate::i18n::tr("menu.copy_view")).clicked() {
+                    ui_state.copy_view_requested = true;
+                    ui.close_menu();
+                }
+                if ui
+                    .button(crate::i18n::tr("menu.copy_view_link"))
+                    .on_hover_text(crate::i18n::tr("menu.copy_view_link_hint"))
+                    .clicked()
+                {
+                    let image = playlist.map(|p| {
+                        let path = p.current();
+                        std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()).display().to_string()
+                    });
+                    // 关闭的暗角 / 接缝羽化不写入链接，与 ev 等字段一样为 0 时省略
+                    let config = &ui_state.config;
+                    let link = DeepLink {
+                        image,
+                        exposure_ev: ui_state.exposure_ev,
+                        vignette: Some(config.vignette_strength).filter(|v| *v != 0.0),
+                        seam_blend_deg: Some(config.seam_blend_deg).filter(|v| *v != 0.0),
+                    };
+                    ui.output_mut(|o| o.copied_text = format!("{}{}", deeplink::LINK_PREFIX, viewer.to_query(&link)));
+                    ui_state.toasts.info(crate::i18n::tr("toast.view_link_copied"));
+                    ui.close_menu();
+                }
+                let can_export = ui_state.image_kind.is_some_and(|k| k != ImageKind::Cubemap);
+                if ui
+                    .add_enabled(can_export, egui::Button::new(crate::i18n::tr("menu.export_rotated")))
+                    .on_hover_text(crate::i18n::tr("menu.export_rotated_hint"))
+                    .clicked()
+                {
+                    let (yaw, _) = viewer.view_center();
+                    ui_state.export_rotation = [yaw, 0.0, 0.0];
+                    ui_state.show_export_rotation = true;
+                    ui.close_menu();
+                }
+
+                ui.separator();
+                if ui.button(crate::i18n::tr("menu.exit")).clicked() {
+                    std::process::exit(0);
+                }
+            });
+
+            // View
+            ui.menu_button(crate::i18n::tr("menu.view"), |ui| {
+                if ui.button(crate::i18n::tr("view.reset")).clicked() {
+                    viewer.reset_view();
+                    ui.close_menu();
+                }
+                if ui.button(crate::i18n::tr("view.fit_entire")).clicked() {
+                    viewer.fit_to_extents();
+                    ui.close_menu();
+                }
+                if ui
+                    .button(crate::i18n::tr("view.reset_all"))
+                    .on_hover_text(crate::i18n::tr("view.reset_all_hint"))
+                    .clicked()
+                {
+                    reset_all_settings(viewer, ui_state);
+                    ui.close_menu();
+                }
+
+                if ui
+                    .button(if viewer.is_fullscreen {
+                        crate::i18n::tr("view.fullscreen.exit")
+                    } else {
+                        crate::i18n::tr("view.fullscreen.enter")
+                    })
+                    .clicked()
+                {
+                    let on = !viewer.is_fullscreen;
+                    set_fullscreen(window, viewer, &ui_state.config, on);
+                    ui.close_menu();
+                }
+
+                ui.menu_button(crate::i18n::tr("view.fullscreen_on"), |ui| {
+                    let mut choice = None;
+                    if ui
+                        .radio(
+                            ui_state.config.fullscreen_monitor.is_none(),
+                            crate::i18n::tr("view.fullscreen_on.current"),
+                        )
+                        .clicked()
+                    {
+                        choice = Some(None);
+                    }
+                    for (i, monitor) in window.available_monitors().enumerate() {
+                        let name = monitor.name();
+                        let size = monitor.size();
+                        let label = format!(
+                            "{}  ({}×{})",
+                            name.clone().unwrap_or_else(|| crate::i18n::tr_with(
+                                "view.fullscreen_on.display",
+                                &[("n", (i + 1).to_string())]
+                            )),
+                            size.width,
+                            size.height
+                        );
+                        let selected = name.is_some() && ui_state.config.fullscreen_monitor == name;
+                        if ui.radio(selected, label).clicked() {
+                            choice = Some(name);
+                        }
+                    }
+                    if let Some(monitor) = choice {
+                        ui_state.config.fullscreen_monitor = monitor;
+                        save_config(ui_state);
+                        set_fullscreen(window, viewer, &ui_state.config, true);
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+                    if ui
+                        .checkbox(
+                            &mut ui_state.config.exclusive_fullscreen,
+                            crate::i18n::tr("view.fullscreen_exclusive"),
+                        )
+                        .on_hover_text(crate::i18n::tr("view.fullscreen_exclusive_hint"))
+                        .changed()
+                    {
+                        save_config(ui_state);
+                        if viewer.is_fullscreen {
+                            set_fullscreen(window, viewer, &ui_state.config, true);
+                        }
+                    }
+                });
+
+                ui.separator();
+                ui.menu_button(crate::i18n::tr("view.projection_mode"), |ui| {
+                    let ray_cast = ui_state.render_path == RenderPath::RayCast;
+                    for mode in ProjectionMode::ALL {
+                        if ui
+                            .add_enabled(
+                                ray_cast || mode == ProjectionMode::Rectilinear,
+                                egui::RadioButton::new(viewer.projection_mode == mode, projection_label(mode)),
+                            )
+                            .on_disabled_hover_text(crate::i18n::tr("view.renderer.mesh_rectilinear_only"))
+                            .clicked()
+                        {
+                            choose_projection(viewer, ui_state, mode);
+                            ui.close_menu();
+                        }
+                    }
+
+                    if let Some(kind) = ui_state.image_kind {
+                        ui.separator();
+                        ui.label(crate::i18n::tr_with("view.image_kind", &[("kind", image_kind_label(kind))]))
+                            .on_hover_text(crate::i18n::tr("view.image_kind_hint"));
+                    }
+
+                    ui.separator();
+                    if ui
+                        .checkbox(
+                            &mut ui_state.config.keep_fov_on_mode_switch,
+                            crate::i18n::tr("view.keep_fov_on_mode_switch"),
+                        )
+                        .changed()
+                    {
+                        save_config(ui_state);
+                    }
+                });
+
+                ui.add_enabled_ui(ui_state.render_path == RenderPath::RayCast, |ui| {
+                    ui.menu_button(crate::i18n::tr("view.split_compare"), |ui| {
+                        let mut enabled = ui_state.split_mode.is_some();
+                        if ui
+                            .checkbox(&mut enabled, crate::i18n::tr("view.split_compare.enable"))
+                            .on_hover_text(crate::i18n::tr("view.split_compare_hint"))
+                            .changed()
+                        {
+                            ui_state.split_mode = enabled.then(|| viewer.projection_mode.cycle(1));
+                        }
+                        ui.separator();
+                        ui.columns(2, |columns| {
+                            columns[0].label(crate::i18n::tr("view.split_compare.left"));
+                            for mode in ProjectionMode::ALL {
+                                if columns[0]
+                                    .radio(viewer.projection_mode == mode, projection_label(mode))
+                                    .clicked()
+                                {
+                                    choose_projection(viewer, ui_state, mode);
+                                }
+                            }
+                            columns[1].label(crate::i18n::tr("view.split_compare.right"));
+                            for mode in ProjectionMode::ALL {
+                                if columns[1].radio(ui_state.split_mode == Some(mode), projection_label(mode)).clicked() {
+                                    ui_state.split_mode = Some(mode);
+                                }
+                            }
+                        });
+                    });
+                })
+                .response
+                .on_disabled_hover_text(crate::i18n::tr("view.renderer.mesh_rectilinear_only"));
+
+                ui.menu_button(crate::i18n::tr("view.seam_blend"), |ui| {
+                    let unit = ui_state.config.angle_unit;
+                    let response = ui
+                        .add(
+                            egui::Slider::new(&mut ui_state.config.seam_blend_deg, 0.0..=10.0)
+                                .custom_formatter(move |deg, _| unit.format(deg as f32))
+                                .custom_parser(move |text| unit.parse(text))
+                                .text(crate::i18n::tr("view.seam_blend_width")),
+                        )
+                        .on_hover_text(crate::i18n::tr("view.seam_blend_hint"));
+                    // 拖动过程中不反复写配置文件，松开后再保存
+                    if response.drag_released() || (response.changed() && !response.dragged()) {
+                        save_config(ui_state);
+                    }
+                    if ui.button(crate::i18n::tr("view.seam_blend_off")).clicked() {
+                        ui_state.config.seam_blend_deg = 0.0;
+                        save_config(ui_state);
+                    }
+                });
+
+                ui.menu_button(crate::i18n::tr("view.vignette"), |ui| {
+                    let response = ui
+                        .add(
+                            egui::Slider::new(&mut ui_state.config.vignette_strength, 0.0..=1.0)
+                                .text(crate::i18n::tr("view.vignette_strength")),
+                        )
+                        .on_hover_text(crate::i18n::tr("view.vignette_hint"));
+                    if response.drag_released() || (response.changed() && !response.dragged()) {
+                        save_config(ui_state);
+                    }
+                    if ui.button(crate::i18n::tr("view.vignette_off")).clicked() {
+                        ui_state.config.vignette_strength = 0.0;
+                        save_config(ui_state);
+                    }
+                });
+
+                ui.menu_button(crate::i18n::tr("view.exposure"), |ui| {
+                    ui.add(
+                        egui::Slider::new(&mut ui_state.exposure_ev, -3.0..=3.0)
+                            .step_by(0.1)
+                            .text(crate::i18n::tr("view.exposure_ev")),
+                    );
+                    if ui
+                        .checkbox(&mut ui_state.config.auto_exposure, crate::i18n::tr("view.auto_exposure"))
+                        .on_hover_text(crate::i18n::tr("view.auto_exposure_hint"))
+                        .changed()
+                    {
+                        if let Some(ev) = ui_state.auto_exposure_ev.filter(|_| ui_state.config.auto_exposure) {
+                            ui_state.exposure_ev = ev;
+                        }
+                        save_config(ui_state);
+                    }
+                    if let Some(ev) = ui_state.auto_exposure_ev {
+                        let text = crate::i18n::tr_with("view.exposure_apply_auto", &[("ev", format!("{:+.1}", ev))]);
+                        if ui.button(text).clicked() {
+                            ui_state.exposure_ev = ev;
+                        }
+                    }
+                    if ui.button(crate::i18n::tr("view.exposure_reset")).clicked() {
+                        ui_state.exposure_ev = 0.0;
+                    }
+                });
+
+                ui.menu_button(crate::i18n::tr("view.alignment"), |ui| {
+                    ui.label(crate::i18n::tr("view.alignment_hint"));
+                    let unit = ui_state.config.angle_unit;
+                    ui.add(
+                        egui::Slider::new(&mut viewer.alignment[0], -45.0..=45.0)
+                            .step_by(0.1)
+                            .custom_formatter(move |deg, _| unit.format(deg as f32))
+                            .custom_parser(move |text| unit.parse(text))
+                            .text(crate::i18n::tr("view.alignment_pitch")),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut viewer.alignment[1], -45.0..=45.0)
+                            .step_by(0.1)
+                            .custom_formatter(move |deg, _| unit.format(deg as f32))
+                            .custom_parser(move |text| unit.parse(text))
+                            .text(crate::i18n::tr("view.alignment_roll")),
+                    );
+                    if ui.button(crate::i18n::tr("view.alignment_reset")).clicked() {
+                        viewer.alignment = [0.0; 2];
+                    }
+                });
+
+                if ui
+                    .checkbox(&mut ui_state.config.linear_source, crate::i18n::tr("view.linear_source"))
+                    .on_hover_text(crate::i18n::tr("view.linear_source_hint"))
+                    .changed()
+                {
+                    save_config(ui_state);
+                }
+
+                ui.menu_button(crate::i18n::tr("view.texture_limit"), |ui| {
+                    let before = ui_state.config.max_texture_size;
+                    let gpu = ui_state.gpu_max_texture.to_string();
+                    ui.radio_value(
+                        &mut ui_state.config.max_texture_size,
+                        None,
+                        crate::i18n::tr_with("view.texture_limit.gpu", &[("size", gpu)]),
+                    );
+                    for size in TEXTURE_LIMIT_PRESETS.into_iter().filter(|&s| s < ui_state.gpu_max_texture) {
+                        ui.radio_value(&mut ui_state.config.max_texture_size, Some(size), size.to_string());
+                    }
+                    ui.label(crate::i18n::tr("view.texture_limit_hint"));
+                    if ui_state.config.max_texture_size != before {
+                        save_config(ui_state);
+                        ui.close_menu();
+                        // 重新打开当前图片，按新的上限上传
+                        if let Some(playlist) = playlist {
+                            *nav = Some(Navigation::Open(playlist.current().to_path_buf()));
+                        }
+                    }
+                });
+
+                ui.menu_button(crate::i18n::tr("view.lens_frames"), |ui| {
+                    for focal in LENS_FRAME_PRESETS {
+                        let mut shown = ui_state.config.lens_frames.contains(&focal);
+                        if ui.checkbox(&mut shown, format!("{}mm", focal)).changed() {
+                            ui_state.config.lens_frames.retain(|&f| f != focal);
+                            if shown {
+                                ui_state.config.lens_frames.push(focal);
+                                ui_state.config.lens_frames.sort_unstable();
+                            }
+                            save_config(ui_state);
+                        }
+                    }
+                });
+
+                ui.menu_button(crate::i18n::tr("view.renderer"), |ui| {
+                    if ui
+                        .radio_value(
+                            &mut ui_state.render_path,
+                            RenderPath::RayCast,
+                            crate::i18n::tr("view.renderer.raycast"),
+                        )
+                        .clicked()
+                    {
+                        ui.close_menu();
+                    }
+                    if ui
+                        .radio_value(
+                            &mut ui_state.render_path,
+                            RenderPath::Mesh,
+                            crate::i18n::tr("view.renderer.mesh"),
+                        )
+                        .on_hover_text(crate::i18n::tr("view.renderer.mesh_rectilinear_only"))
+                        .clicked()
+                    {
+                        ui.close_menu();
+                    }
+                });
+
+                ui.separator();
+                ui.menu_button(crate::i18n::tr("view.input_sensitivity"), |ui| {
+                    ui.add(
+                        egui::Slider::new(&mut viewer.sensitivity_scale, 0.1..=5.0)
+                            .text(crate::i18n::tr("view.multiplier")),
+                    );
+                    if ui.button(crate::i18n::tr("view.reset_1_0")).clicked() {
+                        viewer.sensitivity_scale = 1.0;
+                    }
+
+                    ui.separator();
+                    let unit = ui_state.config.angle_unit;
+                    ui.add_enabled(
+                        !viewer.over_the_pole,
+                        egui::Slider::new(&mut viewer.pitch_limit, 0.0..=90.0)
+                            .custom_formatter(move |deg, _| unit.format(deg as f32))
+                            .custom_parser(move |text| unit.parse(text))
+                            .text(crate::i18n::tr("view.pitch_limit")),
+                    );
+                    ui.checkbox(&mut viewer.over_the_pole, crate::i18n::tr("view.over_the_pole"));
+
+                    ui.separator();
+                    let response = ui.add(
+                        egui::Slider::new(&mut ui_state.config.scroll_zoom.sensitivity, 0.25..=4.0)
+                            .logarithmic(true)
+                            .text(crate::i18n::tr("view.scroll_zoom_sensitivity")),
+                    );
+                    if response.drag_released() || (response.changed() && !response.dragged()) {
+                        save_config(ui_state);
+                    }
+                    if ui
+                        .checkbox(
+                            &mut ui_state.config.scroll_zoom.acceleration,
+                            crate::i18n::tr("view.scroll_zoom_acceleration"),
+                        )
+                        .on_hover_text(crate::i18n::tr("view.scroll_zoom_acceleration_hint"))
+                        .changed()
+                    {
+                        save_config(ui_state);
+                    }
+
+                    ui.separator();
+                    ui.add_enabled(
+                        ui_state.orientation_supported,
+                        egui::Checkbox::new(
+                            &mut ui_state.use_orientation,
+                            crate::i18n::tr("view.device_orientation"),
+                        ),
+                    )
+                    .on_disabled_hover_text(crate::i18n::tr("view.device_orientation_unsupported"));
+                });
+
+                ui.separator();
+                if ui
+                    .checkbox(&mut ui_state.show_fps, crate::i18n::tr("view.show_fps"))
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui.button(crate::i18n::tr("view.fov_limits")).clicked() {
+                    ui_state.show_fov_limits = true;
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut ui_state.show_diagnostics, crate::i18n::tr("view.diagnostics"))
+                    .on_hover_text(crate::i18n::tr("view.diagnostics_hint"))
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut ui_state.show_sun, crate::i18n::tr("view.show_sun"))
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                ui.menu_button(crate::i18n::tr("view.markers"), |ui| {
+                    ui.checkbox(&mut ui_state.show_markers, crate::i18n::tr("view.markers.show"));
+                    ui.separator();
+                    let key = playlist.map(|p| markers::image_key(p.current()));
+                    ui.add_enabled_ui(key.is_some(), |ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut ui_state.marker_text)
+                                .hint_text(crate::i18n::tr("view.markers.text")),
+                        );
+                        let add = ui
+                            .add_enabled(
+                                !ui_state.marker_text.trim().is_empty(),
+                                egui::Button::new(crate::i18n::tr("view.markers.add")),
+                            )
+                            .on_hover_text(crate::i18n::tr("view.markers.add_hint"));
+                        if let Some(key) = key.filter(|_| add.clicked()) {
+                            let (yaw, pitch) = viewer.image_center();
+                            let text = std::mem::take(&mut ui_state.marker_text).trim().to_string();
+                            ui_state.config.markers.entry(key).or_default().push(Marker::new(yaw, pitch, text));
+                            ui_state.show_markers = true;
+                            save_config(ui_state);
+                            ui.close_menu();
+                        }
+                    })
+                    .response
+                    .on_disabled_hover_text(crate::i18n::tr("view.markers.no_file"));
+                });
+                if ui
+                    .checkbox(&mut ui_state.show_eyedropper, crate::i18n::tr("view.eyedropper"))
+                    .on_hover_text(crate::i18n::tr("view.eyedropper_hint"))
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut ui_state.vsync_enabled, crate::i18n::tr("view.enable_vsync"))
+                    .clicked()
+                {
+                    // TODO: Reconfigure
+                }
+
+                ui.menu_button(crate::i18n::tr("view.status_bar"), |ui| {
+                    let items = &mut ui_state.config.status_bar;
+                    let before = *items;
+                    ui.checkbox(&mut items.mode, crate::i18n::tr("view.status_bar.mode"));
+                    ui.checkbox(&mut items.fov, crate::i18n::tr("view.status_bar.fov"));
+                    ui.checkbox(&mut items.focal, crate::i18n::tr("view.status_bar.focal"));
+                    ui.checkbox(&mut items.resolution, crate::i18n::tr("view.status_bar.resolution"));
+                    ui.checkbox(&mut items.yaw, crate::i18n::tr("view.status_bar.yaw"));
+                    ui.checkbox(&mut items.pitch, crate::i18n::tr("view.status_bar.pitch"));
+                    if ui_state.config.status_bar != before {
+                        save_config(ui_state);
+                    }
+                });
+
+                ui.menu_button(crate::i18n::tr("view.yaw_display"), |ui| {
+                    let before = ui_state.config.yaw_display;
+                    ui.radio_value(
+                        &mut ui_state.config.yaw_display,
+                        YawDisplayRange::Signed,
+                        crate::i18n::tr("view.yaw_display.signed"),
+                    );
+                    ui.radio_value(
+                        &mut ui_state.config.yaw_display,
+                        YawDisplayRange::Positive,
+                        crate::i18n::tr("view.yaw_display.positive"),
+                    );
+                    if ui_state.config.yaw_display != before {
+                        save_config(ui_state);
+                        ui.close_menu();
+                    }
+                });
+
+                ui.menu_button(crate::i18n::tr("view.angle_unit"), |ui| {
+                    let before = ui_state.config.angle_unit;
+                    ui.radio_value(
+                        &mut ui_state.config.angle_unit,
+                        AngleUnit::Degrees,
+                        crate::i18n::tr("view.angle_unit.degrees"),
+                    );
+                    ui.radio_value(
+                        &mut ui_state.config.angle_unit,
+                        AngleUnit::Radians,
+                        crate::i18n::tr("view.angle_unit.radians"),
+                    );
+                    if ui_state.config.angle_unit != before {
+                        save_config(ui_state);
+                        ui.close_menu();
+                    }
+                });
+
+                ui.menu_button(crate::i18n::tr("view.reduce_motion"), |ui| {
+                    let before = ui_state.config.reduce_motion;
+                    ui.radio_value(
+                        &mut ui_state.config.reduce_motion,
+                        ReduceMotion::System,
+                        crate::i18n::tr("view.reduce_motion.system"),
+                    );
+                    ui.radio_value(
+                        &mut ui_state.config.reduce_motion,
+                        ReduceMotion::On,
+                        crate::i18n::tr("view.reduce_motion.on"),
+                    );
+                    ui.radio_value(
+                        &mut ui_state.config.reduce_motion,
+                        ReduceMotion::Off,
+                        crate::i18n::tr("view.reduce_motion.off"),
+                    );
+                    ui.label(crate::i18n::tr("view.reduce_motion_hint"));
+                    if ui_state.config.reduce_motion != before {
+                        save_config(ui_state);
+                        ui.close_menu();
+                    }
+                });
+
+                // 减少动态效果时淡入不生效
+                ui.add_enabled_ui(!viewer.reduce_motion, |ui| {
+                    if ui
+                        .checkbox(&mut ui_state.config.fade_in, crate::i18n::tr("view.fade_in"))
+                        .on_hover_text(crate::i18n::tr("view.fade_in_hint"))
+                        .changed()
+                    {
+                        save_config(ui_state);
+                    }
+                });
+            });
+
+            // Language
+            ui.menu_button(crate::i18n::tr("menu.language"), |ui| {
+                let langs: [(&str, &str); 8] = [
+                    ("zh-Hans", "简体中文"),
+                    ("zh-Hant", "繁體中文"),
+                    ("en", "English"),
+                    ("ja", "日本語"),
+                    ("ko", "한국어"),
+                    ("fr", "Français"),
+                    ("ru", "Русский"),
+                    ("ar", "العربية"),
+                ];
+
+                for (code, name) in langs {
+                    if ui
+                        .radio_value(&mut ui_state.current_lang, code.to_string(), name)
+                        .clicked()
+                    {
+                        crate::i18n::init(ui_state.current_lang.clone());
+                        window.set_title(&crate::i18n::tr("app.title"));
+                        ui.close_menu();
+                    }
+                }
+            });
+
+            // Help
+            ui.menu_button(crate::i18n::tr("menu.help"), |ui| {
+                if ui.button(crate::i18n::tr("help.shortcuts")).clicked() {
+                    ui_state.show_shortcuts = true;
+                    ui.close_menu();
+                }
+            });
+        });
+    });
+
+    egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+        // 窗口较窄时自动换行，而不是把右侧的项挤出窗口
+        ui.horizontal_wrapped(|ui| {
+            // 只在两个显示出来的项之间画分隔符
+            let mut first = true;
+            let mut separator = |ui: &mut egui::Ui| {
+                if !std::mem::take(&mut first) {
+                    ui.label("|");
+                }
+            };
+            let items = ui_state.config.status_bar;
+
+            if ui_state.is_loading {
+                separator(ui);
+                ui.label(
+                    egui::RichText::new(crate::i18n::tr("status.loading_image"))
+                        .color(egui::Color32::YELLOW),
+                );
+            } else if let Some(timing) = ui_state.last_load {
+                separator(ui);
+                ui.label(crate::i18n::tr_with(
+                    "status.load_time",
+                    &[
+                        ("decode", timing.decode.as_millis().to_string()),
+                        ("total", timing.total.as_millis().to_string()),
+                    ],
+                ));
+            }
+
+            if let Some(playlist) = playlist {
+                separator(ui);
+                let name = playlist
+                    .current()
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                ui.label(format!("{}/{}  {}", playlist.index() + 1, playlist.len(), name));
+            }
+
+            if let Some(animation) = ui_state.animation.as_mut() {
+                separator(ui);
+                let (icon, hint) = if animation.is_playing() {
+                    ("⏸", "status.animation_pause")
+                } else {
+                    ("▶", "status.animation_play")
+                };
+                if ui.small_button(icon).on_hover_text(crate::i18n::tr(hint)).clicked() {
+                    animation.toggle_playing();
+                }
+                ui.label(crate::i18n::tr_with(
+                    "status.animation_frame",
+                    &[
+                        ("index", (animation.index() + 1).to_string()),
+                        ("count", animation.len().to_string()),
+                    ],
+                ));
+            }
+
+            if items.mode {
+                separator(ui);
+                camera_status_label(
+                    ui,
+                    format!("{} {:?}", crate::i18n::tr("status.mode_prefix"), viewer.projection_mode),
+                    viewer,
+                    &mut ui_state.toasts,
+                );
+            }
+            if items.fov {
+                separator(ui);
+                let fov = ui_state.config.angle_unit.format(viewer.fov);
+                camera_status_label(ui, format!("FOV: {}", fov), viewer, &mut ui_state.toasts);
+            }
+
+            if items.focal {
+                separator(ui);
+                if viewer.projection_mode == ProjectionMode::Equirectangular {
+                    // 平面展开没有“焦距”概念，改为提示交互方式
+                    ui.label(crate::i18n::tr("status.flat_pan_hint"));
+                } else {
+                    let equiv_focal = projection::equivalent_focal_mm(viewer.fov);
+                    ui.label(format!(
+                        "{} {:.1}mm",
+                        crate::i18n::tr("status.equiv_focal_prefix"),
+                        equiv_focal
+                    ));
+                    if let Some(preset) = viewer.focal_preset() {
+                        let hint = ui_state.keybindings.describe(&crate::i18n::tr("status.focal_preset_hint"));
+                        ui.label(egui::RichText::new(format!("[{}mm]", preset)).strong()).on_hover_text(hint);
+                    }
+                }
+            }
+
+            if let Some((texels, ratio)) = ui_state.resolution.filter(|_| items.resolution) {
+                separator(ui);
+                let text = crate::i18n::tr_with(
+                    "status.resolution",
+                    &[("ppd", format!("{:.1}", texels)), ("ratio", format!("{:.2}", ratio))],
+                );
+                if ratio < 1.0 {
+                    ui.label(egui::RichText::new(text).color(egui::Color32::from_rgb(255, 165, 0)))
+                        .on_hover_text(crate::i18n::tr("status.resolution_upscaling"));
+                } else {
+                    ui.label(text);
+                }
+                if let Some((width, height)) = ui_state.texture_size {
+                    ui.label(crate::i18n::tr_with(
+                        "status.texture_size",
+                        &[("width", width.to_string()), ("height", height.to_string())],
+                    ))
+                    .on_hover_text(crate::i18n::tr("status.texture_size_hint"));
+                }
+            }
+
+            if ui_state.show_eyedropper {
+                separator(ui);
+                match ui_state.eyedropper {
+                    Some([r, g, b, _]) => {
+                        egui::color_picker::show_color(ui, egui::Color32::from_rgb(r, g, b), egui::vec2(14.0, 14.0));
+                        ui.label(format!("RGB {r}, {g}, {b}  #{r:02X}{g:02X}{b:02X}"));
+                    }
+                    None => {
+                        ui.label(crate::i18n::tr("status.eyedropper_none"));
+                    }
+                }
+            }
+
+            if items.yaw {
+                separator(ui);
+                let yaw = ui_state.config.angle_unit.format(ui_state.config.yaw_display.normalize(viewer.yaw));
+                camera_status_label(ui, format!("Yaw: {}", yaw), viewer, &mut ui_state.toasts);
+            }
+            if items.pitch {
+                separator(ui);
+                let pitch = ui_state.config.angle_unit.format(viewer.pitch);
+                camera_status_label(ui, format!("Pitch: {}", pitch), viewer, &mut ui_state.toasts);
+            }
+
+            if ui_state.show_fps {
+                separator(ui);
+                ui.label(
+                    egui::RichText::new(format!("FPS: {:.1}", ui_state.fps))
+                        .color(egui::Color32::GREEN),
+                );
+            }
+        });
+    });
+
+    ui_state.toasts.show(ctx);
+
+    // 缩放按钮：给没有滚轮的触控板 / 触屏用户；放在状态栏上方的右下角
+    egui::Area::new("zoom_buttons")
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -36.0))
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                let size = egui::vec2(28.0, 28.0);
+                let (min_fov, max_fov) = viewer.fov_range();
+                if ui
+                    .add_enabled(viewer.fov > min_fov, egui::Button::new("+").min_size(size))
+                    .on_hover_text(crate::i18n::tr("zoom.in"))
+                    .clicked()
+                {
+                    viewer.zoom_by(ZOOM_BUTTON_STEP);
+                }
+                if ui
+                    .add_enabled(viewer.fov < max_fov, egui::Button::new("−").min_size(size))
+                    .on_hover_text(crate::i18n::tr("zoom.out"))
+                    .clicked()
+                {
+                    viewer.zoom_by(-ZOOM_BUTTON_STEP);
+                }
+                if ui
+                    .add(egui::Button::new("1×").min_size(size))
+                    .on_hover_text(crate::i18n::tr("zoom.reset"))
+                    .clicked()
+                {
+                    viewer.reset_zoom();
+                }
+            });
+        });
+
+    if let Some(err) = &ui_state.shader_error {
+        egui::Window::new(crate::i18n::tr("shader.error_title"))
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    ui.label(egui::RichText::new(err).monospace().color(egui::Color32::LIGHT_RED));
+                });
+            });
+    }
+
+    if ui_state.show_diagnostics {
+        draw_diagnostics(ctx, ui_state);
+    }
+
+    if ui_state.show_fov_limits {
+        draw_fov_limits(ctx, viewer, ui_state);
+    }
+
+    if ui_state.show_export_rotation {
+        draw_export_rotation(ctx, viewer, ui_state);
+    }
+
+    if ui_state.show_shortcuts {
+        egui::Window::new(crate::i18n::tr("help.shortcuts_title"))
+            .open(&mut ui_state.show_shortcuts)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("shortcuts").num_columns(2).striped(true).show(ui, |ui| {
+                    for shortcut in SHORTCUTS {
+                        ui.label(egui::RichText::new(shortcut.input_label(&ui_state.keybindings)).strong());
+                        ui.label(crate::i18n::tr(shortcut.action));
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    if ui_state.image_kind.is_none() && !ui_state.is_loading {
+        draw_placeholder(ctx, ui_state);
+    }
+
+    // 分屏对比时只画分隔线和两边的模式名：叠加层按整窗投影计算，放在半边画面上位置不对
+    if split_active(ui_state) {
+        draw_split_labels(ctx, viewer.projection_mode, ui_state.split_mode.unwrap_or(viewer.projection_mode));
+        return;
+    }
+
+    // 平面展开没有镜头视角的概念（状态栏也不显示等效焦距）
+    if viewer.projection_mode != ProjectionMode::Equirectangular {
+        for &focal in &ui_state.config.lens_frames {
+            draw_lens_frame(ctx, viewer, focal, aspect);
+        }
+    }
+
+    if ui_state.show_sun {
+        if let Some(sun) = ui_state.sun {
+            draw_sun_overlay(ctx, viewer, &sun, aspect);
+        }
+    }
+
+    if ui_state.show_markers {
+        if let Some(playlist) = playlist {
+            draw_markers(ctx, viewer, ui_state, &markers::image_key(playlist.current()), aspect);
+        }
+    }
+}
+
+/// “打开图片”对话框：默认同时列出图片和压缩包（立方体面 / 瓦片）
+fn pick_panorama_file() -> Option<PathBuf> {
+    let supported: Vec<&str> = IMAGE_EXTENSIONS.iter().chain(ARCHIVE_EXTENSIONS).copied().collect();
+    rfd::FileDialog::new()
+        .add_filter(crate::i18n::tr("file.filter.supported"), &supported)
+        .add_filter(crate::i18n::tr("file.filter.images"), IMAGE_EXTENSIONS)
+        .add_filter(crate::i18n::tr("file.filter.archives"), ARCHIVE_EXTENSIONS)
+        .pick_file()
+}
+
+/// 取色器：鼠标所指方向上的源图像素；鼠标在界面上、窗口外或平面展开的上下空白处时为 None
+fn pick_source_color(renderer: &Renderer, viewer: &PanoramaViewer3D) -> Option<[u8; 4]> {
+    let ctx = &renderer.egui_ctx;
+    if ctx.is_pointer_over_area() {
+        return None;
+    }
+    let pos = ctx.pointer_hover_pos()?.to_vec2() * ctx.pixels_per_point();
+    let (width, height) = (renderer.size.width as f32, renderer.size.height as f32);
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+    let ndc = glam::Vec2::new(pos.x / width * 2.0 - 1.0, 1.0 - pos.y / height * 2.0);
+    let view = viewer.view_params(renderer.aspect());
+
+    if view.mode == ProjectionMode::Equirectangular {
+        // 与 shader 相同：v 超出 [0, 1] 的区域显示为黑色，没有源像素
+        if !(0.0..=1.0).contains(&projection::flat_uv(ndc, &view).y) {
+            return None;
+        }
+    }
+    renderer.source_pixel(projection::screen_to_direction(ndc, &view))
+}
+
+/// 新图片的纹理就位后更新视角与状态栏
+fn finish_load(pending: PendingLoad, renderer: &Renderer, viewer: &mut PanoramaViewer3D, ui_state: &mut UiState) {
+    viewer.coverage = renderer.coverage();
+    ui_state.last_load = Some(LoadTiming {
+        decode: pending.decode_time,
+        total: pending.worker_time + pending.upload_start.elapsed(),
+    });
+    ui_state.sun = SunPosition::from_metadata(&pending.metadata);
+    viewer.yaw_offset = pending.metadata.heading_deg.map_or(0.0, |h| -h as f32);
+    ui_state.is_loading = false;
+    // 水平校正针对单张图片，换图后重新开始；启动时深链接给出的校正与曝光用于第一张图片
+    let cli_adjustments = std::mem::take(&mut ui_state.cli_adjustments);
+    if !cli_adjustments {
+        viewer.alignment = [0.0; 2];
+    }
+    ui_state.auto_exposure_ev = Some(pending.auto_exposure_ev);
+    if ui_state.config.auto_exposure && !cli_adjustments {
+        ui_state.exposure_ev = pending.auto_exposure_ev;
+    }
+
+    // 同类图片上次用的投影；网格路径只支持透视，不切换
+    ui_state.image_kind = Some(pending.kind);
+    let cli_projection = std::mem::take(&mut ui_state.cli_projection);
+    if let Some(&mode) = ui_state.config.projection_by_kind.get(&pending.kind).filter(|_| !cli_projection) {
+        if ui_state.render_path == RenderPath::RayCast || mode == ProjectionMode::Rectilinear {
+            viewer.set_projection_mode(mode, ui_state.config.keep_fov_on_mode_switch);
+        }
+    }
+
+    // 视角文件优先于记住的投影；其中的标注只在图片还没有保存过标注时使用
+    let cli_view = std::mem::take(&mut ui_state.cli_view);
+    if let Some(view) = pending.view.filter(|_| !cli_view) {
+        view.apply(viewer, ui_state.render_path == RenderPath::RayCast);
+        if !view.markers.is_empty() {
+            ui_state.config.markers.entry(view.image_key).or_insert(view.markers);
+        }
+    }
+}
+
+/// 用户选择投影（菜单 / P 键）：切换并记到当前图片类型下
+fn choose_projection(viewer: &mut PanoramaViewer3D, ui_state: &mut UiState, mode: ProjectionMode) {
+    viewer.set_projection_mode(mode, ui_state.config.keep_fov_on_mode_switch);
+    if let Some(kind) = ui_state.image_kind {
+        ui_state.config.projection_by_kind.insert(kind, mode);
+        save_config(ui_state);
+    }
+}
+
+fn image_kind_label(kind: ImageKind) -> String {
+    crate::i18n::tr(match kind {
+        ImageKind::Equirectangular => "image_kind.equirectangular",
+        ImageKind::Flat => "image_kind.flat",
+        ImageKind::Fisheye => "image_kind.fisheye",
+        ImageKind::Cubemap => "image_kind.cubemap",
+    })
+}
+
+fn projection_label(mode: ProjectionMode) -> String {
+    crate::i18n::tr(match mode {
+        ProjectionMode::Rectilinear => "projection.rectilinear",
+        ProjectionMode::Equidistant => "projection.equidistant",
+        ProjectionMode::Stereographic => "projection.stereographic",
+        ProjectionMode::Pannini => "projection.pannini",
+        ProjectionMode::Equirectangular => "projection.equirectangular",
+        ProjectionMode::Architectural => "projection.architectural",
+    })
+}
+
+/// 按设置决定全屏目标：记住的显示器按名称匹配，找不到时用窗口当前所在的显示器；
+/// 独占全屏选该显示器分辨率最高、其次刷新率最高的视频模式
+fn fullscreen_target(window: &winit::window::Window, config: &Config) -> Fullscreen {
+    let monitor = config.fullscreen_monitor.as_ref().and_then(|name| {
+        window
+            .available_monitors()
+            .find(|m| m.name().as_ref() == Some(name))
+    });
+
+    if config.exclusive_fullscreen {
+        let video_mode = monitor
+            .clone()
+            .or_else(|| window.current_monitor())
+            .and_then(|m| {
+                m.video_modes().max_by_key(|v| {
+                    let size = v.size();
+                    (
+                        size.width as u64 * size.height as u64,
+                        v.refresh_rate_millihertz(),
+                        v.bit_depth(),
+                    )
+                })
+            });
+        if let Some(video_mode) = video_mode {
+            return Fullscreen::Exclusive(video_mode);
+        }
+    }
+
+    Fullscreen::Borderless(monitor)
+}
+
+fn set_fullscreen(window: &winit::window::Window, viewer: &mut PanoramaViewer3D, config: &Config, on: bool) {
+    viewer.is_fullscreen = on;
+    window.set_fullscreen(on.then(|| fullscreen_target(window, config)));
+}
+
+/// 视图菜单中的所有设置恢复默认值（“重置视图”只动相机）；
+/// 界面语言和全屏显示器选择属于环境设置，保持不变
+fn reset_all_settings(viewer: &mut PanoramaViewer3D, ui_state: &mut UiState) {
+    viewer.reset_all();
+
+    ui_state.show_fps = false;
+    ui_state.show_sun = true;
+    ui_state.show_markers = true;
+    ui_state.split_mode = None;
+    ui_state.show_diagnostics = false;
+    ui_state.show_fov_limits = false;
+    ui_state.exposure_ev = 0.0;
+    ui_state.use_orientation = false;
+    ui_state.show_eyedropper = false;
+    ui_state.eyedropper = None;
+    ui_state.render_path = RenderPath::default();
+    ui_state.config = Config {
+        fullscreen_monitor: ui_state.config.fullscreen_monitor.take(),
+        exclusive_fullscreen: ui_state.config.exclusive_fullscreen,
+        // 标注是用户数据而不是设置，保留
+        markers: std::mem::take(&mut ui_state.config.markers),
+        // 快捷键只能在配置文件中修改，也保留
+        keybindings: std::mem::take(&mut ui_state.config.keybindings),
+        last_image: ui_state.config.last_image.take(),
+        ..Config::default()
+    };
+    save_config(ui_state);
+    ui_state.toasts.info(crate::i18n::tr("toast.settings_reset"));
+}
+
+/// 窗口是否处于最小化状态：尺寸为 0，或平台报告已最小化（Wayland 等平台无法得知时只看尺寸）
+fn is_minimized(window: &winit::window::Window) -> bool {
+    let size = window.inner_size();
+    size.width == 0 || size.height == 0 || window.is_minimized() == Some(true)
+}
+
+/// 记住刚打开的文件（播放列表的当前项），供“启动时打开上次的图片”使用；
+/// 标准输入和立方体贴图没有播放列表，不记录
+fn remember_last_image(loader: &ImageLoader, ui_state: &mut UiState) {
+    let Some(path) = loader.playlist().map(|p| p.current()) else {
+        return;
+    };
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if ui_state.config.last_image.as_ref() != Some(&path) {
+        ui_state.config.last_image = Some(path);
+        save_config(ui_state);
+    }
+}
+
+/// 设置有变化时立即写盘，失败时提示
+fn save_config(ui_state: &mut UiState) {
+    if let Err(e) = ui_state.config.save() {
+        ui_state
+            .toasts
+            .error(crate::i18n::tr_with("config.save_failed", &[("err", e)]));
+    }
+}
+
+/// 状态栏中的视角信息（模式 / FOV / Yaw / Pitch）：点击复制可复现当前视角的命令行参数
+fn camera_status_label(ui: &mut egui::Ui, text: String, viewer: &PanoramaViewer3D, toasts: &mut Toasts) {
+    let response = ui
+        .add(egui::Label::new(text).sense(egui::Sense::click()))
+        .on_hover_text(crate::i18n::tr("status.copy_camera_hint"));
+    if response.clicked() {
+        ui.output_mut(|o| o.copied_text = viewer.cli_args());
+        toasts.info(crate::i18n::tr("toast.camera_copied"));
+    }
+}
+
+/// 把当前视图（不含界面）复制到系统剪贴板
+fn copy_view_to_clipboard(
+    renderer: &Renderer,
+    clipboard: &mut Option<arboard::Clipboard>,
+    toasts: &mut Toasts,
+) {
+    let Some(frame) = renderer.capture_frame() else {
+        toasts.error(crate::i18n::tr("toast.copy_view_failed_capture"));
+        return;
+    };
+
+    if clipboard.is_none() {
+        match arboard::Clipboard::new() {
+            Ok(c) => *clipboard = Some(c),
+            Err(e) => {
+                toasts.error(crate::i18n::tr_with("toast.copy_view_failed", &[("err", e.to_string())]));
+                return;
+            }
+        }
+    }
+
+    let (width, height) = frame.dimensions();
+    let image = arboard::ImageData {
+        width: width as usize,
+        height: height as usize,
+        bytes: std::borrow::Cow::Owned(frame.into_raw()),
+    };
+    match clipboard.as_mut().map(|c| c.set_image(image)) {
+        Some(Ok(())) => toasts.info(crate::i18n::tr("toast.view_copied")),
+        Some(Err(e)) => {
+            toasts.error(crate::i18n::tr_with("toast.copy_view_failed", &[("err", e.to_string())]))
+        }
+        None => {}
+    }
+}
+
+/// 画出全画幅等效焦距 focal_mm 的镜头取景框，框线经当前投影映射（鱼眼等模式下为曲线）
+fn draw_lens_frame(ctx: &egui::Context, viewer: &PanoramaViewer3D, focal_mm: u32, aspect: f32) {
+    let view = viewer.view_params(aspect);
+    let rect = ctx.screen_rect();
+    let to_screen = |ndc: glam::Vec2| {
+        egui::pos2(
+            rect.left() + (ndc.x * 0.5 + 0.5) * rect.width(),
+            rect.top() + (0.5 - ndc.y * 0.5) * rect.height(),
+        )
+    };
+
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("lens_frames"),
+    ));
+    let color = egui::Color32::from_rgb(120, 220, 255);
+    let stroke = egui::Stroke::new(1.5, color);
+
+    let outline = projection::lens_frame_outline(focal_mm as f32, &view, 24);
+    for pair in outline.windows(2) {
+        if let [Some(a), Some(b)] = pair {
+            // 相邻采样点在屏幕上跳变过大时说明跨越了投影的不连续处，不连线
+            if (*a - *b).length() < 1.0 {
+                painter.line_segment([to_screen(*a), to_screen(*b)], stroke);
+            }
+        }
+    }
+
+    // 标签放在左上角
+    if let Some(Some(corner)) = outline.first() {
+        painter.text(
+            to_screen(*corner) + egui::vec2(4.0, 2.0),
+            egui::Align2::LEFT_TOP,
+            format!("{}mm", focal_mm),
+            egui::FontId::proportional(12.0),
+            color,
+        );
+    }
+}
+
+/// 最近多久之内的表面错误在诊断窗口中标红
+const RECENT_SURFACE_ERROR: Duration = Duration::from_secs(5);
+
+/// 以度存储的角度输入框，按设置的单位显示和输入
+fn angle_drag_value(value: &mut f32, unit: AngleUnit) -> egui::DragValue<'_> {
+    egui::DragValue::new(value)
+        .speed(0.5)
+        .custom_formatter(move |deg, _| unit.format(deg as f32))
+        .custom_parser(move |text| unit.parse(text))
+}
+
+/// FOV 范围窗口：按投影修改缩放的最小 / 最大 FOV，仍受 hard_fov_range 限制
+fn draw_fov_limits(ctx: &egui::Context, viewer: &mut PanoramaViewer3D, ui_state: &mut UiState) {
+    let unit = ui_state.config.angle_unit;
+    let mut open = true;
+    let mut changed = false;
+    let mut save = false;
+    egui::Window::new(crate::i18n::tr("fov_limits.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let warning = egui::RichText::new(crate::i18n::tr("fov_limits.warning"));
+            ui.label(warning.color(egui::Color32::from_rgb(255, 165, 0)));
+            egui::Grid::new("fov_limits").num_columns(4).striped(true).show(ui, |ui| {
+                ui.label("");
+                ui.label(crate::i18n::tr("fov_limits.min"));
+                ui.label(crate::i18n::tr("fov_limits.max"));
+                ui.end_row();
+
+                for mode in ProjectionMode::ALL {
+                    let (lo, hi) = mode.hard_fov_range();
+                    let custom = ui_state.config.fov_limits.get(&mode).copied();
+                    let [mut min, mut max] = custom.unwrap_or_else(|| mode.default_fov_range().into());
+                    ui.label(projection_label(mode));
+                    let responses = [
+                        ui.add(angle_drag_value(&mut min, unit).clamp_range(lo..=hi)),
+                        ui.add(angle_drag_value(&mut max, unit).clamp_range(lo..=hi)),
+                    ];
+                    if responses.iter().any(|r| r.changed()) {
+                        // 拖动其中一端越过另一端时带着它一起走
+                        if responses[0].changed() {
+                            max = max.max(min);
+                        } else {
+                            min = min.min(max);
+                        }
+                        ui_state.config.fov_limits.insert(mode, [min, max]);
+                        changed = true;
+                    }
+                    save |= responses.iter().any(|r| r.drag_released() || (r.changed() && !r.dragged()));
+                    if ui
+                        .add_enabled(custom.is_some(), egui::Button::new(crate::i18n::tr("fov_limits.reset")))
+                        .clicked()
+                    {
+                        ui_state.config.fov_limits.remove(&mode);
+                        changed = true;
+                        save = true;
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    ui_state.show_fov_limits = open;
+
+    if changed {
+        viewer.fov_limits = ui_state.config.fov_limits.clone();
+        viewer.fov = viewer.clamp_fov(viewer.fov);
+    }
+    if save {
+        save_config(ui_state);
+    }
+}
+
+/// 导出旋转后的全景：选择新的正前方（默认当前视线的方位）和可选的 pitch / roll
+fn draw_export_rotation(ctx: &egui::Context, viewer: &PanoramaViewer3D, ui_state: &mut UiState) {
+    let busy = ui_state.export_rx.is_some();
+    let mut open = true;
+    let mut export = false;
+    egui::Window::new(crate::i18n::tr("export_rotation.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let unit = ui_state.config.angle_unit;
+            let [yaw, pitch, roll] = &mut ui_state.export_rotation;
+            egui::Grid::new("export_rotation").num_columns(2).show(ui, |ui| {
+                ui.label(crate::i18n::tr("export_rotation.yaw"));
+                ui.add(angle_drag_value(yaw, unit));
+                ui.end_row();
+
+                ui.label(crate::i18n::tr("export_rotation.pitch"));
+                ui.add(angle_drag_value(pitch, unit).clamp_range(-90.0..=90.0));
+                ui.end_row();
+
+                ui.label(crate::i18n::tr("export_rotation.roll"));
+                ui.add(angle_drag_value(roll, unit).clamp_range(-180.0..=180.0));
+                ui.end_row();
+            });
+            if ui.button(crate::i18n::tr("export_rotation.use_view")).clicked() {
+                (*yaw, *pitch) = viewer.image_center();
+            }
+            ui.label(crate::i18n::tr("export_rotation.hint"));
+            ui.separator();
+            export = ui.add_enabled(!busy, egui::Button::new(crate::i18n::tr("export_rotation.save"))).clicked();
+        });
+    ui_state.show_export_rotation = open;
+
+    if export {
+        let path = rfd::FileDialog::new()
+            .add_filter(crate::i18n::tr("file.filter.images"), EXPORT_EXTENSIONS)
+            .set_file_name("panorama.jpg")
+            .save_file();
+        if let Some(path) = path {
+            ui_state.export_requested = Some(path);
+            ui_state.show_export_rotation = false;
+        }
+    }
+}
+
+/// 导出支持的格式（由 image crate 按扩展名选择编码器）
+const EXPORT_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "tif", "tiff"];
+
+/// 在后台线程中旋转并保存，完成后从返回的通道取得结果
+/// strip 为条幅全景的垂直范围：只改 yaw 时照样平移，否则先补齐成完整画布再旋转
+fn spawn_export_rotated(
+    source: image::RgbaImage,
+    strip: Option<(f32, f32)>,
+    rotation: [f32; 3],
+    path: PathBuf,
+) -> Receiver<Result<PathBuf, String>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let [yaw, pitch, roll] = rotation;
+        let source = match strip.filter(|_| pitch != 0.0 || roll != 0.0) {
+            Some(range) => crate::resample::expand_strip(&source, range),
+            None => source,
+        };
+        let rotated = crate::resample::rotate_equirect(&source, yaw, pitch, roll);
+        let is_jpeg = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("jpg") || e.eq_ignore_ascii_case("jpeg"));
+        // JPEG 没有 alpha 通道
+        let saved = if is_jpeg {
+            image::DynamicImage::ImageRgba8(rotated).to_rgb8().save(&path)
+        } else {
+            rotated.save(&path)
+        };
+        let result = saved
+            .map(|_| path)
+            .map_err(|e| crate::i18n::tr_with("error.export_failed", &[("err", e.to_string())]));
+        let _ = tx.send(result);
+    });
+    rx
+}
+
+/// 诊断窗口：呈现模式、上一帧的 CPU 耗时和最近的表面错误，用于排查卡顿
+fn draw_diagnostics(ctx: &egui::Context, ui_state: &mut UiState) {
+    let ms = |d: Duration| format!("{:.2} ms", d.as_secs_f64() * 1000.0);
+    let stats = &ui_state.frame_stats;
+    let (present_mode, fps) = (ui_state.present_mode, ui_state.fps);
+    egui::Window::new(crate::i18n::tr("diagnostics.title"))
+        .open(&mut ui_state.show_diagnostics)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("diagnostics").num_columns(2).striped(true).show(ui, |ui| {
+                ui.label(crate::i18n::tr("diagnostics.present_mode"));
+                ui.label(format!("{:?}", present_mode));
+                ui.end_row();
+
+                ui.label(crate::i18n::tr("diagnostics.cpu_time"));
+                ui.label(ms(stats.cpu_time));
+                ui.end_row();
+
+                ui.label(crate::i18n::tr("diagnostics.submit_time"));
+                ui.label(ms(stats.submit_time));
+                ui.end_row();
+
+                ui.label("FPS");
+                ui.label(format!("{:.1}", fps));
+                ui.end_row();
+
+                ui.label(crate::i18n::tr("diagnostics.surface_error"));
+                match &stats.last_surface_error {
+                    Some((err, at)) => {
+                        let text = crate::i18n::tr_with(
+                            "diagnostics.surface_error_ago",
+                            &[("err", format!("{:?}", err)), ("secs", at.elapsed().as_secs().to_string())],
+                        );
+                        let color = if at.elapsed() < RECENT_SURFACE_ERROR {
+                            egui::Color32::LIGHT_RED
+                        } else {
+                            ui.visuals().text_color()
+                        };
+                        ui.label(egui::RichText::new(text).color(color));
+                    }
+                    None => {
+                        ui.label(crate::i18n::tr("diagnostics.surface_error_none"));
+                    }
+                }
+                ui.end_row();
+            });
+        });
+}
+
+/// 是否正在分屏对比（网格路径不支持，与 Renderer 的判断一致）
+fn split_active(ui_state: &UiState) -> bool {
+    ui_state.split_mode.is_some() && ui_state.render_path == RenderPath::RayCast
+}
+
+/// 分屏对比：中间的分隔线和左右两半顶部的投影名称
+fn draw_split_labels(ctx: &egui::Context, left: ProjectionMode, right: ProjectionMode) {
+    let screen = ctx.screen_rect();
+    let visible = ctx.available_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("split_compare"),
+    ));
+    painter.vline(screen.center().x, visible.y_range(), egui::Stroke::new(2.0, egui::Color32::WHITE));
+
+    for (x, mode) in [(screen.left(), left), (screen.center().x, right)] {
+        let galley = painter.layout_no_wrap(
+            projection_label(mode),
+            egui::FontId::proportional(14.0),
+            egui::Color32::WHITE,
+        );
+        let pos = egui::pos2(x + 12.0, visible.top() + 10.0);
+        painter.rect_filled(
+            egui::Rect::from_min_size(pos, galley.size()).expand(4.0),
+            4.0,
+            egui::Color32::from_black_alpha(160),
+        );
+        painter.galley(pos, galley);
+    }
+}
+
+/// 还没有打开图片时画在空场景中央的提示（第一张图片加载完成后不再显示）
+fn draw_placeholder(ctx: &egui::Context, ui_state: &UiState) {
+    let visible = ctx.available_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Background, egui::Id::new("placeholder")));
+    let title = painter.layout_no_wrap(
+        crate::i18n::tr("placeholder.title"),
+        egui::FontId::proportional(24.0),
+        egui::Color32::WHITE,
+    );
+    let hint = painter.layout_no_wrap(
+        ui_state.keybindings.describe(&crate::i18n::tr("placeholder.hint")),
+        egui::FontId::proportional(15.0),
+        egui::Color32::from_gray(200),
+    );
+    let gap = 8.0;
+    let size = egui::vec2(
+        title.size().x.max(hint.size().x),
+        title.size().y + gap + hint.size().y,
+    );
+    let rect = egui::Rect::from_center_size(visible.center(), size);
+    painter.rect_filled(rect.expand(16.0), 8.0, egui::Color32::from_black_alpha(120));
+    painter.galley(egui::pos2(rect.center().x - title.size().x * 0.5, rect.top()), title);
+    painter.galley(egui::pos2(rect.center().x - hint.size().x * 0.5, rect.bottom() - hint.size().y), hint);
+}
+
+/// 标注的操作，画完所有标注后统一应用
+enum MarkerAction {
+    Activate(usize),
+    SetTarget(usize, Option<MarkerTarget>),
+    Delete(usize),
+}
+
+/// 当前图片的标注：经当前投影映射到屏幕，悬停显示文字；
+/// 点击转到跳转目标（没有目标时显示文字），右键菜单设置目标或删除
+fn draw_markers(ctx: &egui::Context, viewer: &mut PanoramaViewer3D, ui_state: &mut UiState, key: &str, aspect: f32) {
+    const RADIUS: f32 = 7.0;
+    let Some(list) = ui_state.config.markers.get(key) else {
+        return;
+    };
+    let view = viewer.view_params(aspect);
+    let rect = ctx.screen_rect();
+    // 菜单栏和状态栏之外的区域；标注不盖在它们上面
+    let visible = ctx.available_rect();
+
+    let mut action = None;
+    for (i, marker) in list.iter().enumerate() {
+        let Some(ndc) = projection::direction_to_screen(marker.direction(), &view) else {
+            continue;
+        };
+        if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 {
+            continue;
+        }
+        let pos = egui::pos2(
+            rect.left() + (ndc.x * 0.5 + 0.5) * rect.width(),
+            rect.top() + (0.5 - ndc.y * 0.5) * rect.height(),
+        );
+        if !visible.contains(pos) {
+            continue;
+        }
+
+        egui::Area::new(egui::Id::new(("marker", i)))
+            .fixed_pos(pos - egui::vec2(RADIUS, RADIUS))
+            .show(ctx, |ui| {
+                let (hit, response) = ui.allocate_exact_size(egui::vec2(RADIUS, RADIUS) * 2.0, egui::Sense::click());
+                // 有跳转目标的标注用蓝色，纯文字标注用黄色
+                let fill = if marker.target.is_some() {
+                    egui::Color32::from_rgb(80, 160, 255)
+                } else {
+                    egui::Color32::from_rgb(255, 200, 40)
+                };
+                let radius = if response.hovered() { RADIUS } else { RADIUS - 1.5 };
+                let painter = ui.painter();
+                painter.circle_filled(hit.center(), radius, fill);
+                painter.circle_stroke(hit.center(), radius, egui::Stroke::new(1.5, egui::Color32::WHITE));
+
+                let response = response.on_hover_text(&marker.text);
+                if response.clicked() {
+                    action = Some(MarkerAction::Activate(i));
+                }
+                response.context_menu(|ui| {
+                    if ui.button(crate::i18n::tr("marker.set_target")).clicked() {
+                        let (yaw, pitch) = viewer.view_center();
+                        action = Some(MarkerAction::SetTarget(i, Some(MarkerTarget { yaw, pitch, fov: viewer.fov })));
+                        ui.close_menu();
+                    }
+                    if marker.target.is_some() && ui.button(crate::i18n::tr("marker.clear_target")).clicked() {
+                        action = Some(MarkerAction::SetTarget(i, None));
+                        ui.close_menu();
+                    }
+                    if ui.button(crate::i18n::tr("marker.delete")).clicked() {
+                        action = Some(MarkerAction::Delete(i));
+                        ui.close_menu();
+                    }
+                });
+            });
+    }
+
+    let Some(action) = action else {
+        return;
+    };
+    let Some(list) = ui_state.config.markers.get_mut(key) else {
+        return;
+    };
+    match action {
+        MarkerAction::Activate(i) => match list[i].target {
+            Some(target) => {
+                viewer.pan = [0.0; 2];
+                viewer.animate_to(target.yaw, target.pitch, target.fov);
+            }
+            None => ui_state.toasts.info(list[i].text.clone()),
+        },
+        MarkerAction::SetTarget(i, target) => {
+            list[i].target = target;
+            save_config(ui_state);
+        }
+        MarkerAction::Delete(i) => {
+            list.remove(i);
+            if list.is_empty() {
+                ui_state.config.markers.remove(key);
+            }
+            save_config(ui_state);
+        }
+    }
+}
+
+/// 把太阳方向经当前投影映射到屏幕，并画一个小标记
+fn draw_sun_overlay(ctx: &egui::Context, viewer: &PanoramaViewer3D, sun: &SunPosition, aspect: f32) {
+    let view = viewer.view_params(aspect);
+    // pitch 为正时视线向下，因此高度角取负；太阳方位相对真实地平线，即水平校正之后的世界
+    let dir = projection::alignment_rotation(&view)
+        * projection::look_direction((sun.yaw_deg as f32).to_radians(), -(sun.altitude_deg as f32).to_radians());
+    let Some(ndc) = projection::direction_to_screen(dir, &view) else {
+        return;
+    };
+    if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 {
+        return;
+    }
+
+    let rect = ctx.screen_rect();
+    let pos = egui::pos2(
+        rect.left() + (ndc.x * 0.5 + 0.5) * rect.width(),
+        rect.top() + (0.5 - ndc.y * 0.5) * rect.height(),
+    );
+
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("sun_overlay"),
+    ));
+    let color = egui::Color32::from_rgb(255, 200, 40);
+    let stroke = egui::Stroke::new(2.0, color);
+    painter.circle_stroke(pos, 8.0, stroke);
+    for i in 0..8 {
+        let a = i as f32 * std::f32::consts::FRAC_PI_4;
+        let d = egui::vec2(a.cos(), a.sin());
+        painter.line_segment([pos + d * 11.0, pos + d * 15.0], stroke);
+    }
+    painter.text(
+        pos + egui::vec2(0.0, 18.0),
+        egui::Align2::CENTER_TOP,
+        crate::i18n::tr_with(
+            "overlay.sun",
+            &[
+                ("az", format!("{:.0}", sun.azimuth_deg)),
+                ("alt", format!("{:.0}", sun.altitude_deg)),
+            ],
+        ),
+        egui::FontId::proportional(12.0),
+        color,
+    );
+}
@@ -0,0 +1,160 @@
+// archive.rs — ZIP 打包的多文件全景（六个立方体面或等矩形瓦片）
+//
+// 在后台加载线程中直接从压缩包读取，不解压到磁盘。布局按文件名识别：
+// - 立方体贴图：与 cubemap.rs 相同的面命名（px/nx/... 或 right/left/...）
+// - 瓦片：文件名主干末尾的两组数字为行、列，如 `pano_0_3.jpg`、`r1c2.png`；
+//   行列需构成完整网格，同一行高度相同、同一列宽度相同，按位置拼成一张等矩形图
+
+use crate::loader::PanoramaImage;
+use crate::playlist::is_image_file;
+use image::RgbaImage;
+use rayon::prelude::*;
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// 可打开的压缩包扩展名
+pub const ARCHIVE_EXTENSIONS: &[&str] = &["zip"];
+
+pub fn is_archive_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| ARCHIVE_EXTENSIONS.iter().any(|x| x.eq_ignore_ascii_case(e)))
+        .unwrap_or(false)
+}
+
+/// 读取压缩包中的全部图片：(包内路径, 文件内容)；
+/// 实际读出的字节合计超过 max_bytes 时中止（不信任包内记录的解压后大小，防止构造的压缩包耗尽内存）
+fn read_images(path: &Path, max_bytes: u64) -> Result<Vec<(PathBuf, Vec<u8>)>, String> {
+    let open_err = |e: &dyn std::fmt::Display| crate::i18n::tr_with("error.zip_open", &[("err", e.to_string())]);
+
+    let file = std::fs::File::open(path).map_err(|e| open_err(&e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| open_err(&e))?;
+
+    let mut images = Vec::new();
+    let mut total = 0u64;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| open_err(&e))?;
+        // enclosed_name 会拒绝 `../` 之类的路径；macOS 打包时附带的 __MACOSX 资源文件也跳过
+        let Some(name) = entry.enclosed_name().map(Path::to_path_buf) else {
+            continue;
+        };
+        if entry.is_dir() || !is_image_file(&name) || name.starts_with("__MACOSX") {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        // 多读一个字节，超出上限时才能发现
+        entry.by_ref().take((max_bytes - total).saturating_add(1)).read_to_end(&mut bytes).map_err(|e| open_err(&e))?;
+        total += bytes.len() as u64;
+        if total > max_bytes {
+            return Err(crate::i18n::tr_with(
+                "error.zip_too_large",
+                &[("max", (max_bytes / (1 << 20)).to_string())],
+            ));
+        }
+        images.push((name, bytes));
+    }
+    Ok(images)
+}
+
+/// 文件名主干中最后两组数字（行, 列）
+fn tile_position(path: &Path) -> Option<(u32, u32)> {
+    let stem = path.file_stem()?.to_str()?;
+    let numbers: Vec<u32> = stem
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().ok())
+        .collect::<Option<_>>()?;
+    match numbers[..] {
+        [.., row, col] => Some((row, col)),
+        _ => None,
+    }
+}
+
+/// 按行列把瓦片拼成一张图；瓦片和拼好的画布都不能超过 max_pixels
+fn assemble_tiles(images: &[(PathBuf, Vec<u8>)], max_pixels: u64) -> Result<RgbaImage, String> {
+    let positions: Vec<(u32, u32)> = images
+        .iter()
+        .map(|(name, _)| tile_position(name))
+        .collect::<Option<_>>()
+        .ok_or_else(|| crate::i18n::tr("error.zip_unknown_layout"))?;
+
+    // 行列号不要求从 0 开始或连续，按排序后的位置编号
+    let rows: Vec<u32> = positions.iter().map(|p| p.0).collect::<BTreeSet<_>>().into_iter().collect();
+    let cols: Vec<u32> = positions.iter().map(|p| p.1).collect::<BTreeSet<_>>().into_iter().collect();
+    let unique: BTreeSet<_> = positions.iter().collect();
+    if rows.len() * cols.len() != images.len() || unique.len() != images.len() {
+        return Err(crate::i18n::tr_with(
+            "error.zip_tile_grid",
+            &[("rows", rows.len().to_string()), ("cols", cols.len().to_string()), ("n", images.len().to_string())],
+        ));
+    }
+
+    let files: Vec<&[u8]> = images.iter().map(|(_, bytes)| bytes.as_slice()).collect();
+    crate::loader::check_total_dimensions(&files, max_pixels)?;
+    let tiles: Vec<RgbaImage> =
+        files.par_iter().map(|bytes| crate::loader::decode_limited(bytes, max_pixels)).collect::<Result<_, _>>()?;
+    let grid: Vec<(usize, usize)> = positions
+        .iter()
+        .map(|(r, c)| (rows.binary_search(r).unwrap_or(0), cols.binary_search(c).unwrap_or(0)))
+        .collect();
+
+    // 第 0 行决定每列宽度，第 0 列决定每行高度
+    let mut col_widths = vec![0; cols.len()];
+    let mut row_heights = vec![0; rows.len()];
+    for (tile, &(r, c)) in tiles.iter().zip(&grid) {
+        if r == 0 {
+            col_widths[c] = tile.width();
+        }
+        if c == 0 {
+            row_heights[r] = tile.height();
+        }
+    }
+    if tiles.iter().zip(&grid).any(|(tile, &(r, c))| tile.dimensions() != (col_widths[c], row_heights[r])) {
+        return Err(crate::i18n::tr("error.zip_tile_size"));
+    }
+
+    let offsets = |sizes: &[u32]| -> Vec<u32> {
+        sizes.iter().scan(0, |acc, &s| {
+            let start = *acc;
+            *acc += s;
+            Some(start)
+        }).collect()
+    };
+    let (xs, ys) = (offsets(&col_widths), offsets(&row_heights));
+    let (width, height) = (col_widths.iter().sum(), row_heights.iter().sum());
+    crate::loader::check_pixels(width, height, max_pixels)?;
+    let mut canvas = RgbaImage::new(width, height);
+    for (tile, &(r, c)) in tiles.iter().zip(&grid) {
+        image::imageops::replace(&mut canvas, tile, xs[c] as i64, ys[r] as i64);
+    }
+    Ok(canvas)
+}
+
+/// 读取并解码压缩包中的全景：优先识别为立方体贴图，否则按瓦片拼接。
+/// 像素上限 max_pixels 按所有面 / 瓦片合计计算，读取的文件内容也不超过对应的解码内存上限
+pub fn decode_zip(path: &Path, max_pixels: u64) -> Result<PanoramaImage, String> {
+    let images = read_images(path, crate::loader::max_alloc_bytes(max_pixels))?;
+    if images.is_empty() {
+        return Err(crate::i18n::tr("error.zip_no_images"));
+    }
+
+    let names: Vec<PathBuf> = images.iter().map(|(name, _)| name.clone()).collect();
+    if let Ok(faces) = crate::cubemap::assign_faces(&names) {
+        let files: Vec<&[u8]> = faces
+            .iter()
+            .map(|face| {
+                let (_, bytes) = images.iter().find(|(name, _)| name == face).expect("face comes from the archive");
+                bytes.as_slice()
+            })
+            .collect();
+        crate::loader::check_total_dimensions(&files, max_pixels)?;
+        let faces: Vec<RgbaImage> = files
+            .par_iter()
+            .map(|bytes| crate::loader::decode_limited(bytes, max_pixels))
+            .collect::<Result<_, _>>()?;
+        return crate::cubemap::check_faces(faces).map(|faces| PanoramaImage::Cubemap(Box::new(faces)));
+    }
+
+    assemble_tiles(&images, max_pixels).map(PanoramaImage::Equirect)
+}
@@ -0,0 +1,82 @@
+// bench.rs — 性能基准：`--bench [frames]`
+//
+// 加载程序生成的固定测试图，在光线投射路径下按 ProjectionMode::ALL 逐个投影离屏渲染 frames 帧
+// （默认 DEFAULT_FRAMES），网格路径再测一次透视，然后退出。结果以 CSV 写到标准输出
+// （日志仍在标准错误），便于脚本比较前后两次的结果：
+//   path,mode,width,height,frames,avg_ms
+//   raycast,rectilinear,1280,720,300,0.412
+// 渲染分辨率即窗口的初始尺寸；曝光、暗角等调整保持默认值，不读取配置文件。
+
+use crate::mesh_renderer::RenderPath;
+use crate::panorama::ProjectionMode;
+use crate::renderer::Renderer;
+use image::{Rgba, RgbaImage};
+
+pub const DEFAULT_FRAMES: u32 = 300;
+
+/// 每种投影正式计时前先渲染的帧数，管线与驱动第一次使用的开销不计入结果
+const WARMUP_FRAMES: u32 = 10;
+
+/// 测试图宽度（2:1）；4096 在常见 GPU 的纹理限制内，不会走缩小版 + 细节纹理的路径
+const TEST_IMAGE_WIDTH: u32 = 4096;
+
+/// `--bench [frames]` 的帧数；没有 --bench 时返回 None
+pub fn frames_from_args() -> Option<u32> {
+    let mut it = std::env::args().skip(1).peekable();
+    it.find(|a| a == "--bench")?;
+    // 值可省略，规则与 cli::path_from_args 相同
+    let Some(text) = it.next_if(|v| !v.starts_with("--")) else {
+        return Some(DEFAULT_FRAMES);
+    };
+    match text.parse::<u32>() {
+        Ok(frames) if frames > 0 => Some(frames),
+        _ => {
+            crate::cli::warn_invalid("--bench", &text);
+            Some(DEFAULT_FRAMES)
+        }
+    }
+}
+
+/// 固定的测试图：横竖两个方向的渐变叠加 64 像素棋盘格，避免大片纯色让纹理缓存显得偏快
+pub fn test_image() -> RgbaImage {
+    let (w, h) = (TEST_IMAGE_WIDTH, TEST_IMAGE_WIDTH / 2);
+    RgbaImage::from_fn(w, h, |x, y| {
+        let checker = if (x / 64 + y / 64) % 2 == 0 { 0 } else { 64 };
+        Rgba([(x * 191 / w) as u8 + checker, (y * 191 / h) as u8 + checker, 128, 255])
+    })
+}
+
+/// 依次测量每种投影，结果写到标准输出
+pub fn run(renderer: &mut Renderer, frames: u32) {
+    renderer.load_panorama(test_image());
+    let (width, height) = (renderer.size.width, renderer.size.height);
+
+    let runs = ProjectionMode::ALL
+        .into_iter()
+        .map(|mode| (RenderPath::RayCast, mode))
+        .chain([(RenderPath::Mesh, ProjectionMode::Rectilinear)]);
+
+    println!("path,mode,width,height,frames,avg_ms");
+    for (path, mode) in runs {
+        if let Err(e) = renderer.set_render_path(path) {
+            log::warn!("{}", crate::i18n::tr_with("error.mesh_renderer", &[("err", e)]));
+            continue;
+        }
+        renderer.update_camera(30.0, 10.0, mode.default_fov(), mode);
+        renderer.bench_frames(WARMUP_FRAMES);
+        let average = renderer.bench_frames(frames);
+        let path_name = match path {
+            RenderPath::RayCast => "raycast",
+            RenderPath::Mesh => "mesh",
+        };
+        println!(
+            "{},{},{},{},{},{:.3}",
+            path_name,
+            mode.cli_name(),
+            width,
+            height,
+            frames,
+            average.as_secs_f64() * 1000.0
+        );
+    }
+}
@@ -0,0 +1,164 @@
+// cli.rs — 命令行选项一览、--help / --version 与启动图片路径
+//
+// 新增命令行选项时请同步加到 OPTIONS：--help 的输出和路径参数的解析都依赖这张表。
+// 各选项的值仍由各自的模块读取（i18n::resolve_lang_from_args、RenderPath::from_args 等）。
+
+/// 选项的取值方式
+pub enum Value {
+    /// 不带值
+    None,
+    /// 必须带值（显示用的占位符）
+    Required(&'static str),
+    /// 值可省略：下一个参数不以 `--` 开头时才当作值
+    Optional(&'static str),
+}
+
+pub struct CliOption {
+    /// 长选项名
+    pub flag: &'static str,
+    /// 短选项名（如 `-h`）
+    pub short: Option<&'static str>,
+    pub value: Value,
+    /// 说明文字的 i18n key
+    pub help: &'static str,
+}
+
+impl CliOption {
+    fn matches(&self, arg: &str) -> bool {
+        arg == self.flag || self.short == Some(arg)
+    }
+
+    fn label(&self) -> String {
+        let names = match self.short {
+            Some(short) => format!("{}, {}", short, self.flag),
+            None => format!("    {}", self.flag),
+        };
+        match self.value {
+            Value::None => names,
+            Value::Required(v) => format!("{} <{}>", names, v),
+            Value::Optional(v) => format!("{} [{}]", names, v),
+        }
+    }
+}
+
+const fn option(flag: &'static str, value: Value, help: &'static str) -> CliOption {
+    CliOption { flag, short: None, value, help }
+}
+
+pub const OPTIONS: &[CliOption] = &[
+    option("--lang", Value::Required("code"), "cli.lang"),
+    option("--renderer", Value::Required("raycast|mesh"), "cli.renderer"),
+    option("--shader-dev", Value::Optional("path"), "cli.shader_dev"),
+    option("--projection", Value::Required("mode"), "cli.projection"),
+    option("--fov", Value::Required("deg"), "cli.fov"),
+    option("--yaw", Value::Required("deg"), "cli.yaw"),
+    option("--pitch", Value::Required("deg"), "cli.pitch"),
+    option("--open", Value::Required("link"), "cli.open"),
+    option("--max-megapixels", Value::Required("n"), "cli.max_megapixels"),
+    option("--log-level", Value::Required("level"), "cli.log_level"),
+    option("--log-file", Value::Optional("path"), "cli.log_file"),
+    option("--bench", Value::Optional("frames"), "cli.bench"),
+    CliOption { flag: "--help", short: Some("-h"), value: Value::None, help: "cli.help" },
+    CliOption { flag: "--version", short: Some("-V"), value: Value::None, help: "cli.version" },
+];
+
+/// 可代替命令行选项的环境变量：(名称, 说明的 i18n key)
+pub const ENV_VARS: &[(&str, &str)] = &[
+    ("PANORAMA_LANG", "cli.env.lang"),
+    ("PANORAMA_SHADER_DEV", "cli.env.shader_dev"),
+];
+
+fn find(arg: &str) -> Option<&'static CliOption> {
+    OPTIONS.iter().find(|o| o.matches(arg))
+}
+
+/// 处理 --help / --version：打印后直接退出；需要在 i18n::init 之后调用，帮助文字按当前语言输出
+pub fn handle_info_flags() {
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", usage());
+                std::process::exit(0);
+            }
+            "-V" | "--version" => {
+                println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+                std::process::exit(0);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// --help 的完整文字
+pub fn usage() -> String {
+    let name = env!("CARGO_PKG_NAME");
+    // 说明文字左对齐到最长的选项名之后
+    let labels: Vec<String> = OPTIONS.iter().map(CliOption::label).collect();
+    let width = labels.iter().map(String::len).chain(ENV_VARS.iter().map(|(v, _)| v.len())).max().unwrap_or(0) + 4;
+    let mut text = format!("{} {}\n\n", name, env!("CARGO_PKG_VERSION"));
+    text += &crate::i18n::tr_with("cli.usage", &[("name", name.to_string())]);
+    text += "\n\n";
+
+    text += &crate::i18n::tr("cli.arguments");
+    text += "\n";
+    text += &format!("  {:<width$}{}\n\n", "<path> | -", crate::i18n::tr("cli.path"));
+
+    text += &crate::i18n::tr("cli.options");
+    text += "\n";
+    for (option, label) in OPTIONS.iter().zip(&labels) {
+        text += &format!("  {:<width$}{}\n", label, crate::i18n::tr(option.help));
+    }
+    text += "\n";
+
+    text += &crate::i18n::tr("cli.environment");
+    text += "\n";
+    for (var, help) in ENV_VARS {
+        text += &format!("  {:<width$}{}\n", var, crate::i18n::tr(help));
+    }
+    text
+}
+
+/// 选项 flag 之后的一个参数；未指定时返回 None
+pub fn value(flag: &str) -> Option<String> {
+    let mut it = std::env::args().skip(1);
+    it.find(|a| a == flag)?;
+    it.next()
+}
+
+/// 数值选项；无法解析时输出提示并忽略
+pub fn number(flag: &str) -> Option<f32> {
+    let text = value(flag)?;
+    let number = text.parse().ok();
+    if number.is_none() {
+        warn_invalid(flag, &text);
+    }
+    number
+}
+
+pub fn warn_invalid(flag: &str, value: &str) {
+    log::warn!(
+        "{}",
+        crate::i18n::tr_with("log.invalid_cli_value", &[("flag", flag.to_string()), ("value", value.to_string())])
+    );
+}
+
+/// 命令行中第一个不是选项的参数：启动时打开的图片，`-` 表示从标准输入读取
+pub fn path_from_args() -> Option<String> {
+    let mut it = std::env::args().skip(1).peekable();
+    while let Some(a) = it.next() {
+        match find(&a).map(|o| &o.value) {
+            // 带参数的选项：跳过其参数
+            Some(Value::Required(_)) => {
+                it.next();
+            }
+            // 值可省略，规则与 shader_dev::path_from_args 相同
+            Some(Value::Optional(_)) => {
+                it.next_if(|v| !v.starts_with("--"));
+            }
+            Some(Value::None) => {}
+            None if a.starts_with("--") => {}
+            None => return Some(a),
+        }
+    }
+    None
+}
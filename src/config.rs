@@ -0,0 +1,190 @@
+// config.rs — 用户设置（JSON，保存在系统配置目录）
+//
+// 位置：<config_dir>/panorama_viewer/config.json
+// （Windows: %APPDATA%，macOS: ~/Library/Application Support，Linux: ~/.config）
+// 所有字段都有默认值：文件缺失、字段缺失或格式错误时回退默认，不影响启动。
+
+use crate::markers::Marker;
+use crate::motion::ReduceMotion;
+use crate::panorama::{ImageKind, ProjectionMode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// 状态栏 yaw 的显示范围（内部存储的 yaw 是连续值，不受影响）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum YawDisplayRange {
+    /// (-180°, 180°]
+    #[default]
+    Signed,
+    /// [0°, 360°)
+    Positive,
+}
+
+impl YawDisplayRange {
+    pub fn normalize(self, yaw_deg: f32) -> f32 {
+        match self {
+            YawDisplayRange::Signed => {
+                let wrapped = (yaw_deg + 180.0).rem_euclid(360.0) - 180.0;
+                if wrapped == -180.0 {
+                    180.0
+                } else {
+                    wrapped
+                }
+            }
+            YawDisplayRange::Positive => {
+                let wrapped = yaw_deg.rem_euclid(360.0);
+                // rem_euclid 对极小的负数可能返回 360.0
+                if wrapped >= 360.0 {
+                    0.0
+                } else {
+                    wrapped
+                }
+            }
+        }
+    }
+}
+
+/// 状态栏显示哪些相机信息（窗口较窄时可以只留关心的几项）；
+/// 加载进度、文件名、动图帧号、取色结果与 FPS 跟随各自的功能显示，不在这里设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusBarItems {
+    pub mode: bool,
+    pub fov: bool,
+    /// 等效焦距（平面展开模式下为操作提示）
+    pub focal: bool,
+    /// 源图分辨率（像素/度）
+    pub resolution: bool,
+    pub yaw: bool,
+    pub pitch: bool,
+}
+
+impl Default for StatusBarItems {
+    fn default() -> Self {
+        Self { mode: true, fov: true, focal: true, resolution: true, yaw: true, pitch: true }
+    }
+}
+
+/// 滚轮缩放的手感
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScrollZoom {
+    /// 乘在每格 FOV 步长上的倍率
+    pub sensitivity: f32,
+    /// 快速连续滚动时逐格加大步长；关闭时每格步长固定（线性）
+    pub acceleration: bool,
+}
+
+impl Default for ScrollZoom {
+    fn default() -> Self {
+        Self { sensitivity: 1.0, acceleration: false }
+    }
+}
+
+/// 状态栏与角度输入框的显示单位（内部统一以度存储）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians,
+}
+
+impl AngleUnit {
+    /// 角度（度）-> 显示文字，如 "30.0°" 或 "0.524 rad"
+    pub fn format(self, deg: f32) -> String {
+        match self {
+            AngleUnit::Degrees => format!("{:.1}°", deg),
+            AngleUnit::Radians => format!("{:.3} rad", deg.to_radians()),
+        }
+    }
+
+    /// 输入框中的文字（可带单位）-> 度
+    pub fn parse(self, text: &str) -> Option<f64> {
+        let number = text.trim().trim_end_matches("rad").trim_end_matches('°').trim();
+        let value: f64 = number.parse().ok()?;
+        Some(match self {
+            AngleUnit::Degrees => value,
+            AngleUnit::Radians => value.to_degrees(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Config {
+    pub yaw_display: YawDisplayRange,
+    pub angle_unit: AngleUnit,
+    /// 切换投影模式时保留当前 FOV，而不是跳到该模式的默认值
+    pub keep_fov_on_mode_switch: bool,
+    /// 画出取景框的全画幅等效焦距（mm）
+    pub lens_frames: Vec<u32>,
+    /// 等矩形接缝（u=0/1）两侧的羽化宽度（度），0 为关闭
+    pub seam_blend_deg: f32,
+    /// 全屏使用的显示器名称；None 或找不到该显示器时使用窗口当前所在的显示器
+    pub fullscreen_monitor: Option<String>,
+    /// 独占全屏（切换显示器视频模式），否则为无边框全屏
+    pub exclusive_fullscreen: bool,
+    /// 暗角强度（0..1，画面四角压暗的比例），0 为关闭；适合展示 / 自助终端场景
+    pub vignette_strength: f32,
+    /// 每种图片类型上次使用的投影，打开同类图片时自动切换
+    pub projection_by_kind: BTreeMap<ImageKind, ProjectionMode>,
+    /// 各图片上的标注点，键为图片绝对路径（见 markers::image_key）
+    pub markers: BTreeMap<String, Vec<Marker>>,
+    /// 源图像素已是线性值（如部分 HDR 转存的 PNG），采样时不做 sRGB 解码
+    pub linear_source: bool,
+    /// 打开图片时按亮度直方图自动设置曝光（见 resample::auto_exposure_ev）
+    pub auto_exposure: bool,
+    /// 按动作名覆盖默认快捷键，值为 VirtualKeyCode 名称（见 keybindings.rs）
+    pub keybindings: BTreeMap<String, Vec<String>>,
+    pub status_bar: StatusBarItems,
+    /// 减少动态效果：视角变化不再有过渡动画（见 motion.rs）
+    pub reduce_motion: ReduceMotion,
+    /// 纹理边长上限（低于 GPU 上限，节省显存）；None 为使用 GPU 上限
+    pub max_texture_size: Option<u32>,
+    /// 新图片上传完成后短暂淡入（减少动态效果时不生效）
+    pub fade_in: bool,
+    /// 按投影自定义的 FOV 范围（度，[min, max]），见 PanoramaViewer3D::fov_limits
+    pub fov_limits: BTreeMap<ProjectionMode, [f32; 2]>,
+    /// 启动时没有指定图片则重新打开 last_image
+    pub reopen_last_image: bool,
+    /// 最近一次成功打开的图片文件（绝对路径；标准输入和立方体贴图不记录）
+    pub last_image: Option<PathBuf>,
+    pub scroll_zoom: ScrollZoom,
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("panorama_viewer").join("config.json"))
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let Some(path) = config_path() else {
+            return Self::default();
+        };
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&text).unwrap_or_else(|e| {
+            log::warn!(
+                "{}",
+                crate::i18n::tr_with(
+                    "config.parse_failed",
+                    &[("path", path.display().to_string()), ("err", e.to_string())]
+                )
+            );
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = config_path().ok_or_else(|| crate::i18n::tr("config.no_config_dir"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, text).map_err(|e| e.to_string())
+    }
+}
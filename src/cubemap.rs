@@ -0,0 +1,121 @@
+// cubemap.rs — 六面体（立方体贴图）全景的文件识别与解码
+//
+// 面的顺序与 wgpu 立方体纹理的层一致：+X, -X, +Y, -Y, +Z, -Z，
+// 对应常见天空盒命名 right / left / top / bottom / front / back。
+// 每张图都是“从立方体内部看出去”的样子；top 的下边缘与 front 相接，bottom 的上边缘与 front 相接。
+
+use glam::{Vec2, Vec3};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// 每个面可接受的文件名关键字（不区分大小写，按文件名主干匹配）
+const FACE_KEYWORDS: [&[&str]; 6] = [
+    &["px", "posx", "right", "r"],
+    &["nx", "negx", "left", "l"],
+    &["py", "posy", "top", "up", "u"],
+    &["ny", "negy", "bottom", "down", "d"],
+    &["pz", "posz", "front", "f"],
+    &["nz", "negz", "back", "b"],
+];
+
+/// 文件名主干按 `_ - . 空格` 拆分后的最后一段，如 `sky_px`、`Room-front`
+fn face_token(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?.to_lowercase();
+    stem.rsplit(['_', '-', '.', ' ']).next().map(str::to_string)
+}
+
+fn face_index(path: &Path) -> Option<usize> {
+    let token = face_token(path)?;
+    FACE_KEYWORDS.iter().position(|keys| keys.contains(&token.as_str()))
+}
+
+/// 从一组文件中找出六个面；缺面或重复时返回错误说明
+pub fn assign_faces(paths: &[PathBuf]) -> Result<[PathBuf; 6], String> {
+    let mut faces: [Option<PathBuf>; 6] = Default::default();
+    for path in paths.iter().filter(|p| crate::playlist::is_image_file(p)) {
+        if let Some(i) = face_index(path) {
+            if faces[i].is_some() {
+                return Err(crate::i18n::tr_with(
+                    "error.cubemap_duplicate_face",
+                    &[("face", FACE_KEYWORDS[i][2].to_string())],
+                ));
+            }
+            faces[i] = Some(path.clone());
+        }
+    }
+
+    let missing: Vec<&str> = faces
+        .iter()
+        .zip(FACE_KEYWORDS.iter())
+        .filter(|(f, _)| f.is_none())
+        .map(|(_, keys)| keys[2])
+        .collect();
+    if !missing.is_empty() {
+        return Err(crate::i18n::tr_with(
+            "error.cubemap_missing_faces",
+            &[("faces", missing.join(", "))],
+        ));
+    }
+
+    Ok(faces.map(|f| f.expect("checked above")))
+}
+
+/// 文件夹内的所有图片文件
+pub fn files_in_folder(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).collect())
+        .unwrap_or_default()
+}
+
+/// 并行解码六个面，并检查它们都是同样大小的正方形；
+/// 六个面合计的像素数不能超过 max_pixels（与单张图片的 --max-megapixels 相同），超过时不解码
+pub fn decode_faces(paths: &[PathBuf; 6], max_pixels: u64) -> Result<[image::RgbaImage; 6], String> {
+    let files: Vec<Vec<u8>> = paths
+        .iter()
+        .map(|path| {
+            std::fs::read(path).map_err(|e| crate::i18n::tr_with("error.open_file", &[("err", e.to_string())]))
+        })
+        .collect::<Result<_, _>>()?;
+    let slices: Vec<&[u8]> = files.iter().map(Vec::as_slice).collect();
+    crate::loader::check_total_dimensions(&slices, max_pixels)?;
+    let faces: Vec<image::RgbaImage> = slices
+        .par_iter()
+        .map(|bytes| crate::loader::decode_limited(bytes, max_pixels))
+        .collect::<Result<_, _>>()?;
+    check_faces(faces)
+}
+
+/// 检查六个面都是同样大小的正方形（顺序已按 FACE_KEYWORDS 排好）
+pub fn check_faces(faces: Vec<image::RgbaImage>) -> Result<[image::RgbaImage; 6], String> {
+    let size = faces[0].dimensions();
+    if size.0 != size.1 || faces.iter().any(|f| f.dimensions() != size) {
+        return Err(crate::i18n::tr("error.cubemap_face_size"));
+    }
+
+    Ok(faces.try_into().expect("six faces"))
+}
+
+/// 世界方向 -> (面序号, 面内 UV)，与 shader sample_cube 的采样一致：
+/// 先翻转 z（“前”面对应相机的 -Z），再按 wgpu 立方体贴图的主轴规则选面，UV 原点在左上角
+pub fn direction_to_face(dir: Vec3) -> (usize, Vec2) {
+    let d = Vec3::new(dir.x, dir.y, -dir.z);
+    let a = d.abs();
+    let (face, sc, tc, ma) = if a.x >= a.y && a.x >= a.z {
+        if d.x > 0.0 {
+            (0, -d.z, -d.y, a.x)
+        } else {
+            (1, d.z, -d.y, a.x)
+        }
+    } else if a.y >= a.z {
+        if d.y > 0.0 {
+            (2, d.x, d.z, a.y)
+        } else {
+            (3, d.x, -d.z, a.y)
+        }
+    } else if d.z > 0.0 {
+        (4, d.x, -d.y, a.z)
+    } else {
+        (5, -d.x, -d.y, a.z)
+    };
+    (face, Vec2::new(sc / ma + 1.0, tc / ma + 1.0) * 0.5)
+}
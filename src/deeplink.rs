@@ -0,0 +1,136 @@
+// deeplink.rs — 可分享的视角链接：把图片、相机与调整参数编码成一段 URL 查询串
+//
+// 格式：panorama-viewer://view?image=<路径>&yaw=30&pitch=-10&fov=70&projection=pannini&tilt=1.5&roll=-2&pan=10,5&ev=0.3
+//       &vignette=0.4&seam=2
+// - yaw / pitch / fov 为相机字段本身（平面展开的平移单独放在 pan），数值按 f32 的最短表示输出，
+//   解析回来与原值完全相同
+// - tilt / roll 为水平校正（PanoramaViewer3D::alignment），ev 为曝光补偿；为 0 时省略
+// - vignette 为暗角强度，seam 为接缝羽化宽度（度）；只在链接带有时覆盖本机的设置
+// - image 可省略；值按 RFC 3986 做百分号编码
+// 编解码见 PanoramaViewer3D::to_query / from_query；--open 接受完整链接或只有查询串的部分。
+
+/// 复制出的完整链接的前缀
+pub const LINK_PREFIX: &str = "panorama-viewer://view?";
+
+/// 链接中不属于相机的部分
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeepLink {
+    /// 图片路径；None 表示链接只描述视角
+    pub image: Option<String>,
+    /// 曝光补偿（EV）
+    pub exposure_ev: f32,
+    /// 暗角强度（0..1）；None 表示沿用当前设置
+    pub vignette: Option<f32>,
+    /// 接缝羽化宽度（度）；None 表示沿用当前设置
+    pub seam_blend_deg: Option<f32>,
+}
+
+/// 完整链接或查询串中 `?` 之后的部分
+pub fn query_part(link: &str) -> &str {
+    link.split_once('?').map_or(link, |(_, query)| query)
+}
+
+/// 拆成 (key, value) 列表，值已解码；空的片段忽略
+pub fn parse_pairs(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let (key, value) = part.split_once('=').unwrap_or((part, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+/// 百分号编码：只保留 RFC 3986 的非保留字符，其余按 UTF-8 字节编码
+pub fn encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// 百分号解码；`+` 按表单习惯视为空格，无效的转义原样保留
+pub fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                out.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::panorama::{PanoramaViewer3D, ProjectionMode};
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let path = r"C:\全景 照片\a&b=c+d%.jpg";
+        assert_eq!(decode(&encode(path)), path);
+        assert_eq!(query_part(&format!("{}a=1", LINK_PREFIX)), "a=1");
+        assert_eq!(query_part("a=1"), "a=1");
+    }
+
+    #[test]
+    fn view_query_round_trip() {
+        let mut viewer = PanoramaViewer3D::new();
+        viewer.set_projection_mode(ProjectionMode::Equirectangular, true);
+        viewer.yaw = 33.333_33;
+        viewer.pitch = -12.345_678;
+        viewer.fov = 97.654_32;
+        viewer.pan = [-41.1, 3.3];
+        viewer.alignment = [1.25, -2.0 / 3.0];
+        let link = DeepLink {
+            image: Some("/tmp/pano #1.jpg".to_string()),
+            exposure_ev: 0.7,
+            vignette: Some(0.35),
+            seam_blend_deg: Some(2.5),
+        };
+
+        let query = viewer.to_query(&link);
+        let mut parsed = PanoramaViewer3D::new();
+        let parsed_link = parsed.from_query(&query);
+        assert_eq!(parsed_link, link);
+        assert_eq!(parsed.projection_mode, viewer.projection_mode);
+        assert_eq!((parsed.yaw, parsed.pitch, parsed.fov), (viewer.yaw, viewer.pitch, viewer.fov));
+        assert_eq!(parsed.pan, viewer.pan);
+        assert_eq!(parsed.alignment, viewer.alignment);
+    }
+
+    /// 非有限的数值（NaN / inf）不能进入相机参数，忽略后保持原值
+    #[test]
+    fn view_query_rejects_non_finite() {
+        let mut viewer = PanoramaViewer3D::new();
+        viewer.set_projection_mode(ProjectionMode::Equirectangular, true);
+        let link = viewer.from_query("yaw=NaN&fov=inf&roll=-inf&pan=NaN,0&ev=NaN&vignette=inf&seam=NaN");
+        assert_eq!(link, DeepLink::default());
+        assert_eq!(viewer.pan, [0.0; 2]);
+        viewer.from_query("pan=5,inf&projection=equirectangular");
+        assert_eq!(viewer.pan, [0.0; 2]);
+        assert!([viewer.yaw, viewer.pitch, viewer.fov, viewer.alignment[0], viewer.alignment[1]]
+            .iter()
+            .all(|v| v.is_finite()));
+    }
+}
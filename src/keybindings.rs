@@ -0,0 +1,196 @@
+// keybindings.rs — 可在配置文件中修改的快捷键
+//
+// config.json 的 keybindings 按动作名覆盖默认按键，值为 winit VirtualKeyCode 的名称：
+//   "keybindings": { "open": ["L"], "west": ["A", "Numpad4"] }
+// 没有列出的动作保持默认；无法识别的动作名或按键名输出提示后忽略。
+// 修饰键的含义固定不变（Ctrl+复制、Shift+反向切换投影），只有主键可以修改。
+
+use std::collections::{BTreeMap, HashMap};
+use winit::event::VirtualKeyCode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Open,
+    Previous,
+    Next,
+    CopyView,
+    North,
+    East,
+    South,
+    West,
+    LevelHorizon,
+    Reset,
+    Fit,
+    FocalShorter,
+    FocalLonger,
+    CycleProjection,
+    ToggleAnimation,
+    Fullscreen,
+    Help,
+}
+
+impl Action {
+    /// 同一个键绑定了多个动作时，排在前面的生效
+    pub const ALL: [Action; 17] = [
+        Action::Open,
+        Action::Previous,
+        Action::Next,
+        Action::CopyView,
+        Action::North,
+        Action::East,
+        Action::South,
+        Action::West,
+        Action::LevelHorizon,
+        Action::Reset,
+        Action::Fit,
+        Action::FocalShorter,
+        Action::FocalLonger,
+        Action::CycleProjection,
+        Action::ToggleAnimation,
+        Action::Fullscreen,
+        Action::Help,
+    ];
+
+    /// 配置文件中的动作名
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Open => "open",
+            Action::Previous => "previous",
+            Action::Next => "next",
+            Action::CopyView => "copy_view",
+            Action::North => "north",
+            Action::East => "east",
+            Action::South => "south",
+            Action::West => "west",
+            Action::LevelHorizon => "level_horizon",
+            Action::Reset => "reset",
+            Action::Fit => "fit",
+            Action::FocalShorter => "focal_shorter",
+            Action::FocalLonger => "focal_longer",
+            Action::CycleProjection => "cycle_projection",
+            Action::ToggleAnimation => "toggle_animation",
+            Action::Fullscreen => "fullscreen",
+            Action::Help => "help",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    fn default_keys(self) -> &'static [VirtualKeyCode] {
+        use VirtualKeyCode as K;
+        match self {
+            Action::Open => &[K::O],
+            Action::Previous => &[K::Left],
+            Action::Next => &[K::Right],
+            Action::CopyView => &[K::C],
+            Action::North => &[K::N, K::Numpad8],
+            Action::East => &[K::E, K::Numpad6],
+            Action::South => &[K::S, K::Numpad2],
+            Action::West => &[K::W, K::Numpad4],
+            Action::LevelHorizon => &[K::H, K::Numpad5],
+            Action::Reset => &[K::R],
+            Action::Fit => &[K::Z],
+            Action::FocalShorter => &[K::PageDown],
+            Action::FocalLonger => &[K::PageUp],
+            Action::CycleProjection => &[K::P],
+            Action::ToggleAnimation => &[K::Space],
+            Action::Fullscreen => &[K::F11],
+            Action::Help => &[K::F1],
+        }
+    }
+}
+
+/// VirtualKeyCode 的名称（与 winit 的 serde 表示相同，如 "O"、"F11"、"Numpad8"、"Key1"）
+fn parse_key(name: &str) -> Option<VirtualKeyCode> {
+    serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+}
+
+/// 帮助窗口中显示的按键名
+fn key_label(key: VirtualKeyCode) -> String {
+    use VirtualKeyCode as K;
+    match key {
+        K::Left => "←".to_string(),
+        K::Right => "→".to_string(),
+        K::Up => "↑".to_string(),
+        K::Down => "↓".to_string(),
+        other => {
+            let name = format!("{:?}", other);
+            // 小键盘数字只显示数字，Key1 之类显示为 1
+            name.strip_prefix("Numpad")
+                .or_else(|| name.strip_prefix("Key"))
+                .filter(|rest| rest.len() == 1 && rest.chars().all(|c| c.is_ascii_digit()))
+                .map(str::to_string)
+                .unwrap_or(name)
+        }
+    }
+}
+
+/// 合并默认值与配置后的按键表
+#[derive(Debug, Clone)]
+pub struct Keybindings {
+    keys: BTreeMap<&'static str, Vec<VirtualKeyCode>>,
+    actions: HashMap<VirtualKeyCode, Action>,
+}
+
+impl Keybindings {
+    /// overrides 即 Config::keybindings：动作名 -> 按键名列表（空列表表示取消绑定）
+    pub fn new(overrides: &BTreeMap<String, Vec<String>>) -> Self {
+        for name in overrides.keys().filter(|n| Action::from_name(n).is_none()) {
+            log::warn!("{}", crate::i18n::tr_with("config.unknown_action", &[("name", name.clone())]));
+        }
+
+        let mut keys = BTreeMap::new();
+        let mut actions = HashMap::new();
+        for action in Action::ALL {
+            let bound: Vec<VirtualKeyCode> = match overrides.get(action.name()) {
+                Some(names) => names
+                    .iter()
+                    .filter_map(|name| {
+                        let key = parse_key(name);
+                        if key.is_none() {
+                            log::warn!("{}", crate::i18n::tr_with("config.unknown_key", &[("name", name.clone())]));
+                        }
+                        key
+                    })
+                    .collect(),
+                None => action.default_keys().to_vec(),
+            };
+            for &key in &bound {
+                actions.entry(key).or_insert(action);
+            }
+            keys.insert(action.name(), bound);
+        }
+        Self { keys, actions }
+    }
+
+    pub fn action(&self, key: VirtualKeyCode) -> Option<Action> {
+        self.actions.get(&key).copied()
+    }
+
+    /// 把模板中的 `{动作名}` 换成绑定的按键，如 "{cycle_projection} / Shift+{cycle_projection}" -> "P / Shift+P"；
+    /// 多个按键时第一个为主键，其余放在括号里
+    pub fn describe(&self, template: &str) -> String {
+        let mut text = template.to_string();
+        for (name, keys) in &self.keys {
+            let label = match keys.split_first() {
+                None => "—".to_string(),
+                Some((first, [])) => key_label(*first),
+                Some((first, rest)) => format!(
+                    "{} ({})",
+                    key_label(*first),
+                    rest.iter().map(|k| key_label(*k)).collect::<Vec<_>>().join(" / ")
+                ),
+            };
+            text = text.replace(&format!("{{{}}}", name), &label);
+        }
+        text
+    }
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new())
+    }
+}
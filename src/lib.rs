@@ -0,0 +1,64 @@
+// lib.rs — 全景渲染器库
+
+//! 全景渲染器库：可嵌入到自己的 winit / egui 程序中。
+//!
+//! 独立程序（app::run）只是这个库的一个使用者。嵌入时的最小流程：
+//!
+//! ```no_run
+//! use panorama_viewer::panorama::PanoramaViewer3D;
+//! use panorama_viewer::renderer::Renderer;
+//! use std::sync::Arc;
+//!
+//! fn embed(window: Arc<winit::window::Window>, img: image::RgbaImage) {
+//!     // 文字通过 i18n 查表；不初始化时直接显示 key
+//!     panorama_viewer::i18n::init("en");
+//!
+//!     let mut renderer = pollster::block_on(Renderer::new(window.clone()));
+//!     let viewer = PanoramaViewer3D::new();
+//!     renderer.load_panorama(img);
+//!
+//!     // 每帧（RedrawRequested）：
+//!     renderer.update_camera(viewer.yaw, viewer.pitch, viewer.fov, viewer.projection_mode);
+//!     renderer
+//!         .render_with_ui(&window, |ctx| {
+//!             // 在这里画自己的 egui 界面
+//!             let _ = ctx;
+//!         })
+//!         .unwrap();
+//! }
+//! ```
+//!
+//! 窗口事件先交给 `renderer.egui_state.on_event(&renderer.egui_ctx, &event)`，
+//! 窗口尺寸变化时调用 `renderer.resize(new_size)`；视角交互（拖拽、缩放）由调用方修改
+//! `PanoramaViewer3D` 的字段，可参考 app.rs。需要同步其它视图或叠加 3D 内容时，
+//! `PanoramaViewer3D::view_pose` 给出与 shader 一致的相机旋转和投影参数；
+//! 已加载图片的像素可以通过 `Renderer::source_image` 读取，不必重新解码。
+
+pub mod animation;
+pub mod archive;
+pub mod app;
+pub mod bench;
+pub mod cli;
+pub mod config;
+pub mod cubemap;
+pub mod deeplink;
+pub mod i18n;
+pub mod keybindings;
+pub mod loader;
+pub mod logging;
+pub mod markers;
+pub mod mesh;
+pub mod mesh_renderer;
+pub mod metadata;
+pub mod motion;
+pub mod orientation;
+pub mod panorama;
+pub mod playlist;
+pub mod projection;
+pub mod renderer;
+pub mod resample;
+pub mod shader_dev;
+pub mod shortcuts;
+pub mod sidecar;
+pub mod sun;
+pub mod toast;
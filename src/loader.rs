@@ -0,0 +1,627 @@
+// loader.rs — 后台线程解码图片，并预取播放列表中的下一张
+//
+// 当前图片与预取各用一个通道，并各带一个代号（generation）：
+// 用户跳转后代号递增，迟到的旧结果直接丢弃，不会覆盖新图片。
+// 缩放到 GPU 纹理限制、补齐画布等 CPU 处理也在后台线程完成，主线程只剩纹理上传。
+// 解码前先读文件头检查尺寸，超过像素上限（--max-megapixels）的图片直接报错，不分配内存。
+
+use crate::archive;
+use crate::cubemap;
+use crate::metadata::{self, ImageMetadata};
+use crate::panorama::{Coverage, ImageKind};
+use crate::playlist::Playlist;
+use crate::resample;
+use crate::sidecar::{self, ViewSidecar};
+
+use image::io::Reader as ImageReader;
+use image::RgbaImage;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 解码得到的全景像素
+pub enum PanoramaImage {
+    Equirect(image::RgbaImage),
+    /// 六个面，顺序见 cubemap.rs
+    Cubemap(Box<[image::RgbaImage; 6]>),
+    /// 动图（GIF / APNG，多于一帧）：每帧已合成为完整画面，附带显示时长
+    Animated(Vec<(image::RgbaImage, Duration)>),
+}
+
+impl PanoramaImage {
+    /// 图片类型；要在上传前处理（补齐画布）之前调用，之后就看不出原始比例了
+    pub fn kind(&self, has_gpano: bool) -> ImageKind {
+        let (width, height) = match self {
+            PanoramaImage::Equirect(img) => img.dimensions(),
+            PanoramaImage::Animated(frames) => frames.first().map_or((0, 0), |(img, _)| img.dimensions()),
+            PanoramaImage::Cubemap(_) => return ImageKind::Cubemap,
+        };
+        ImageKind::classify(width, height, has_gpano)
+    }
+
+    /// 像素数据占用的内存（字节）
+    pub fn byte_size(&self) -> usize {
+        match self {
+            PanoramaImage::Equirect(img) => img.as_raw().len(),
+            PanoramaImage::Animated(frames) => frames.iter().map(|(img, _)| img.as_raw().len()).sum(),
+            PanoramaImage::Cubemap(faces) => faces.iter().map(|img| img.as_raw().len()).sum(),
+        }
+    }
+}
+
+/// 默认的解码像素上限（百万像素）：10 亿像素的 RGBA 约 4 GB，足够容纳常见的超大全景
+pub const DEFAULT_MAX_MEGAPIXELS: u64 = 1000;
+
+/// 解码结果；失败时为已本地化的错误信息，由主线程显示
+pub type LoadResult = Result<LoadedImage, String>;
+
+/// 小于该值的帧间隔按浏览器惯例视为 100ms（很多 GIF 把 0 写作“尽快”）
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// 解码 GIF / APNG 的全部帧；不是动图（或只有一帧）时返回 None，按静态图片处理
+/// 所有帧合计超过 max_bytes 时中止，返回 Limits 错误
+fn decode_animation(bytes: &[u8], max_bytes: u64) -> Option<image::ImageResult<Vec<(image::RgbaImage, Duration)>>> {
+    use image::codecs::{gif::GifDecoder, png::PngDecoder};
+    use image::AnimationDecoder;
+
+    let collect = |frames: image::Frames| {
+        let mut collected = Vec::new();
+        let mut total = 0u64;
+        for frame in frames {
+            let frame = frame?;
+            total += frame.buffer().len() as u64;
+            if total > max_bytes {
+                return Err(image::ImageError::Limits(image::error::LimitError::from_kind(
+                    image::error::LimitErrorKind::InsufficientMemory,
+                )));
+            }
+            collected.push(frame);
+        }
+        Ok(collected)
+    };
+
+    let frames = match image::guess_format(bytes).ok()? {
+        image::ImageFormat::Gif => GifDecoder::new(Cursor::new(bytes)).and_then(|d| collect(d.into_frames())),
+        image::ImageFormat::Png => {
+            let decoder = PngDecoder::new(Cursor::new(bytes)).ok()?;
+            if !decoder.is_apng() {
+                return None;
+            }
+            collect(decoder.apng().into_frames())
+        }
+        _ => return None,
+    };
+
+    match frames {
+        Ok(frames) if frames.len() > 1 => Some(Ok(frames
+            .into_iter()
+            .map(|f| {
+                let delay = Duration::from(f.delay());
+                let delay = if delay < MIN_FRAME_DELAY { DEFAULT_FRAME_DELAY } else { delay };
+                (f.into_buffer(), delay)
+            })
+            .collect())),
+        Ok(_) => None,
+        Err(e) => Some(Err(e)),
+    }
+}
+
+/// 后台线程解码完成后发回主线程的数据
+pub struct LoadedImage {
+    pub image: PanoramaImage,
+    pub metadata: ImageMetadata,
+    /// 解码耗时（ImageReader::decode + 转 RGBA）
+    pub decode_time: Duration,
+    /// 后台线程总耗时（读文件 + 解码 + 转换 + 元数据 + 上传前处理）
+    pub worker_time: Duration,
+    /// 局部全景的有效内容范围（上传前处理时得出）；完整全景或立方体贴图为 None
+    pub coverage: Option<Coverage>,
+    /// 超过 GPU 纹理限制的等矩形图：保留的全分辨率画布（image 中是缩小后的版本），
+    /// 渲染器放大时从中裁出可见部分作为细节纹理
+    pub full_resolution: Option<RgbaImage>,
+    /// 按原始尺寸判断的图片类型
+    pub kind: ImageKind,
+    /// 自动曝光建议值（EV，上传前处理时得出，见 resample::auto_exposure_ev）
+    pub auto_exposure_ev: f32,
+    /// 图片旁的初始视角文件（见 sidecar.rs）
+    pub view: Option<ViewSidecar>,
+}
+
+/// 命令行中表示“从标准输入读取图片”的路径
+pub const STDIN_PATH: &str = "-";
+
+/// 读取并解码一张图片；失败时返回可直接显示的错误信息（打不开文件、图片过大、解码失败等）
+/// 像素数超过 max_pixels 的图片不解码，返回“图片过大”错误；压缩包按所有文件合计的像素数检查
+pub fn decode_file(path: &Path, max_pixels: u64) -> LoadResult {
+    let start = Instant::now();
+    log::info!(
+        "{}",
+        crate::i18n::tr_with("log.loading_image_bg", &[("path", format!("{:?}", path))])
+    );
+
+    // 压缩包：立方体面或瓦片，直接在内存中解码
+    if archive::is_archive_file(path) {
+        return archive::decode_zip(path, max_pixels).map(|image| {
+            let elapsed = start.elapsed();
+            LoadedImage {
+                kind: image.kind(false),
+                image,
+                metadata: ImageMetadata::default(),
+                decode_time: elapsed,
+                worker_time: elapsed,
+                coverage: None,
+                full_resolution: None,
+                auto_exposure_ev: 0.0,
+                view: sidecar::load(path),
+            }
+        });
+    }
+
+    // 整个文件读入内存：解码与 EXIF/XMP 元数据解析共用同一份字节
+    let bytes = std::fs::read(path)
+        .map_err(|e| crate::i18n::tr_with("error.open_file", &[("err", format!("{}", e))]))?;
+
+    decode_bytes(&bytes, start, max_pixels).map(|loaded| LoadedImage { view: sidecar::load(path), ..loaded })
+}
+
+/// 从标准输入读取全部字节并解码（如 `curl ... | panorama_viewer -`）
+pub fn decode_stdin(max_pixels: u64) -> LoadResult {
+    let start = Instant::now();
+    log::info!("{}", crate::i18n::tr("log.loading_image_stdin"));
+
+    // 输入长度未知，读取量不超过解码的内存上限；多读一个字节用来判断是否超出
+    let max_bytes = max_alloc_bytes(max_pixels);
+    let mut bytes = Vec::new();
+    std::io::stdin()
+        .lock()
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .map_err(|e| crate::i18n::tr_with("error.read_stdin", &[("err", e.to_string())]))?;
+    if bytes.is_empty() {
+        return Err(crate::i18n::tr("error.stdin_empty"));
+    }
+    if bytes.len() as u64 > max_bytes {
+        return Err(too_large_memory_error(max_pixels));
+    }
+
+    decode_bytes(&bytes, start, max_pixels)
+}
+
+/// 解码时的内存上限：按 16 位 RGBA（每像素 8 字节）留足余量，尺寸检查漏掉的情况（如动图的帧数）由它兜底
+pub(crate) fn max_alloc_bytes(max_pixels: u64) -> u64 {
+    max_pixels.saturating_mul(8)
+}
+
+/// 只读文件头取得的尺寸；无法识别的格式返回 None
+fn header_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()
+        .and_then(|r| r.into_dimensions().ok())
+}
+
+/// w×h 超过像素上限时返回“图片过大”错误
+pub(crate) fn check_pixels(w: u32, h: u32, max_pixels: u64) -> Result<(), String> {
+    if w as u64 * h as u64 <= max_pixels {
+        return Ok(());
+    }
+    Err(crate::i18n::tr_with(
+        "error.image_too_large",
+        &[
+            ("w", w.to_string()),
+            ("h", h.to_string()),
+            ("mp", (w as u64 * h as u64 / 1_000_000).to_string()),
+            ("max", (max_pixels / 1_000_000).to_string()),
+        ],
+    ))
+}
+
+/// 只读文件头取得尺寸，超过像素上限时返回“图片过大”错误；无法识别的格式交给解码时报错
+fn check_dimensions(bytes: &[u8], max_pixels: u64) -> Result<(), String> {
+    match header_dimensions(bytes) {
+        Some((w, h)) => check_pixels(w, h, max_pixels),
+        None => Ok(()),
+    }
+}
+
+/// 多文件全景（立方体面、瓦片）解码前的检查：每个文件以及所有文件合计的像素数都不能超过 max_pixels
+pub(crate) fn check_total_dimensions(files: &[&[u8]], max_pixels: u64) -> Result<(), String> {
+    let mut total = 0u64;
+    for bytes in files {
+        if let Some((w, h)) = header_dimensions(bytes) {
+            check_pixels(w, h, max_pixels)?;
+            total += w as u64 * h as u64;
+        }
+    }
+    if total > max_pixels {
+        return Err(crate::i18n::tr_with(
+            "error.images_too_large",
+            &[("mp", (total / 1_000_000).to_string()), ("max", (max_pixels / 1_000_000).to_string())],
+        ));
+    }
+    Ok(())
+}
+
+/// 解码所需内存超过上限时的错误
+pub(crate) fn too_large_memory_error(max_pixels: u64) -> String {
+    crate::i18n::tr_with(
+        "error.image_too_large_memory",
+        &[("max", (max_alloc_bytes(max_pixels) / (1 << 20)).to_string())],
+    )
+}
+
+/// 解码多文件全景中的单个文件（立方体面、瓦片），内存上限与单张图片相同；尺寸应已由 check_total_dimensions 检查
+pub(crate) fn decode_limited(bytes: &[u8], max_pixels: u64) -> Result<RgbaImage, String> {
+    ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(image::ImageError::IoError)
+        .and_then(|mut r| {
+            let mut limits = image::io::Limits::no_limits();
+            limits.max_alloc = Some(max_alloc_bytes(max_pixels));
+            r.limits(limits);
+            r.decode()
+        })
+        .map(resample::into_rgba8)
+        .map_err(|e| match e {
+            image::ImageError::Limits(_) => too_large_memory_error(max_pixels),
+            e => crate::i18n::tr_with("error.decode_image", &[("err", e.to_string())]),
+        })
+}
+
+/// 从内存中的文件内容解码图片并读取元数据；start 为整个加载的起始时间
+fn decode_bytes(bytes: &[u8], start: Instant, max_pixels: u64) -> LoadResult {
+    check_dimensions(bytes, max_pixels)?;
+    let max_bytes = max_alloc_bytes(max_pixels);
+
+    // 解码（JPEG 解码器开启 rayon 后按分量并行）与元数据解析同时进行
+    let decode_start = Instant::now();
+    let (img_result, metadata) = rayon::join(
+        || {
+            if let Some(frames) = decode_animation(bytes, max_bytes) {
+                return frames.map(PanoramaImage::Animated);
+            }
+            ImageReader::new(Cursor::new(bytes))
+                .with_guessed_format()
+                .map_err(image::ImageError::IoError)
+                .and_then(|mut r| {
+                    let mut limits = image::io::Limits::no_limits();
+                    limits.max_alloc = Some(max_bytes);
+                    r.limits(limits);
+                    r.decode()
+                })
+                .map(|img| PanoramaImage::Equirect(resample::into_rgba8(img)))
+        },
+        || metadata::read_metadata(bytes),
+    );
+    let decode_time = decode_start.elapsed();
+    // 解码器忽略 EXIF 方向（手机拍的全景可能因此上下颠倒），在这里转正
+    let img_result = img_result.map(|image| match (image, metadata.orientation) {
+        (PanoramaImage::Equirect(rgba), Some(orientation)) => {
+            PanoramaImage::Equirect(resample::apply_exif_orientation(rgba, orientation))
+        }
+        (image, _) => image,
+    });
+
+    match img_result {
+        Ok(image) => {
+            match &image {
+                PanoramaImage::Animated(frames) => {
+                    let (w, h) = frames[0].0.dimensions();
+                    log::info!(
+                        "{}",
+                        crate::i18n::tr_with(
+                            "log.animation_loaded",
+                            &[
+                                ("w", w.to_string()),
+                                ("h", h.to_string()),
+                                ("frames", frames.len().to_string())
+                            ]
+                        )
+                    );
+                }
+                PanoramaImage::Equirect(rgba) => {
+                    let (w, h) = rgba.dimensions();
+                    log::info!(
+                        "{}",
+                        crate::i18n::tr_with(
+                            "log.image_loaded_size",
+                            &[("w", w.to_string()), ("h", h.to_string())]
+                        )
+                    );
+                }
+                PanoramaImage::Cubemap(_) => {}
+            }
+            log::info!(
+                "{}",
+                crate::i18n::tr_with("log.image_memory", &[("mb", (image.byte_size() / (1 << 20)).to_string())])
+            );
+
+            Ok(LoadedImage {
+                kind: image.kind(metadata.crop.is_some()),
+                image,
+                metadata,
+                decode_time,
+                worker_time: start.elapsed(),
+                coverage: None,
+                full_resolution: None,
+                auto_exposure_ev: 0.0,
+                view: None,
+            })
+        }
+        Err(image::ImageError::Limits(_)) => Err(too_large_memory_error(max_pixels)),
+        Err(e) => Err(crate::i18n::tr_with("error.decode_image", &[("err", format!("{}", e))])),
+    }
+}
+
+/// 把解码结果处理成可以直接上传的纹理：缩放到 GPU 限制内、按 GPano 裁切信息补齐画布；
+/// 补齐后的画布超过像素上限时返回“图片过大”错误
+fn prepare_for_upload(loaded: LoadedImage, limits: DecodeLimits) -> LoadResult {
+    let max_texture_dimension = limits.max_texture_dimension;
+    let start = Instant::now();
+    let crop = loaded.metadata.crop;
+    // 在补齐画布之前统计，局部全景补的黑边不算在内
+    let auto_exposure_ev = match &loaded.image {
+        PanoramaImage::Equirect(img) => resample::auto_exposure_ev(&[img]),
+        PanoramaImage::Animated(frames) => frames.first().map_or(0.0, |(img, _)| resample::auto_exposure_ev(&[img])),
+        PanoramaImage::Cubemap(faces) => resample::auto_exposure_ev(&faces.iter().collect::<Vec<_>>()),
+    };
+    let mut full_resolution = None;
+    let (image, coverage) = match loaded.image {
+        PanoramaImage::Equirect(img) => {
+            // 先在原分辨率下补齐画布；放不进 GPU 时上传缩小版，原图留在内存里供细节纹理使用。
+            // GPano 的完整画布可能是原图的许多倍，分配前按像素上限检查
+            let (canvas_w, canvas_h) = resample::padded_dimensions(img.width(), img.height(), crop);
+            check_pixels(canvas_w, canvas_h, limits.max_pixels)?;
+            let (img, coverage) = resample::prepare_equirect(img, crop, u32::MAX, false);
+            match resample::downscale_to_fit(&img, max_texture_dimension) {
+                Some(base) => {
+                    full_resolution = Some(img);
+                    (PanoramaImage::Equirect(base), coverage)
+                }
+                None => (PanoramaImage::Equirect(img), coverage),
+            }
+        }
+        PanoramaImage::Animated(frames) => {
+            use rayon::prelude::*;
+            // 所有帧尺寸相同，只在第一帧打印缩放日志
+            let prepared: Vec<_> = frames
+                .into_par_iter()
+                .enumerate()
+                .map(|(i, (img, delay))| {
+                    let (img, coverage) = resample::prepare_equirect(img, crop, max_texture_dimension, i == 0);
+                    (img, coverage, delay)
+                })
+                .collect();
+            let coverage = prepared.first().and_then(|(_, coverage, _)| *coverage);
+            let frames = prepared.into_iter().map(|(img, _, delay)| (img, delay)).collect();
+            (PanoramaImage::Animated(frames), coverage)
+        }
+        PanoramaImage::Cubemap(faces) => {
+            let faces = resample::fit_cube_faces(*faces, max_texture_dimension);
+            (PanoramaImage::Cubemap(Box::new(faces)), None)
+        }
+    };
+    Ok(LoadedImage {
+        image,
+        coverage,
+        full_resolution,
+        auto_exposure_ev,
+        worker_time: loaded.worker_time + start.elapsed(),
+        ..loaded
+    })
+}
+
+/// 解码上限，随每次加载传给后台线程
+#[derive(Clone, Copy)]
+struct DecodeLimits {
+    /// GPU 纹理边长上限，后台线程据此缩放
+    max_texture_dimension: u32,
+    /// 解码像素上限
+    max_pixels: u64,
+}
+
+/// 后台线程中的收尾：处理成可上传的纹理，失败时打印错误，再把结果发回主线程
+fn finish_decode(result: LoadResult, limits: DecodeLimits, generation: u64, tx: &Sender<(u64, LoadResult)>) {
+    let result = result.and_then(|loaded| prepare_for_upload(loaded, limits));
+    if let Err(e) = &result {
+        log::error!("{}", e);
+    }
+    if tx.send((generation, result)).is_err() {
+        log::error!("{}", crate::i18n::tr("error.send_to_main_failed"));
+    }
+}
+
+fn spawn_decode(path: PathBuf, generation: u64, limits: DecodeLimits, tx: Sender<(u64, LoadResult)>) {
+    thread::spawn(move || {
+        let result = decode_file(&path, limits.max_pixels);
+        finish_decode(result, limits, generation, &tx);
+    });
+}
+
+pub struct ImageLoader {
+    playlist: Option<Playlist>,
+
+    tx: Sender<(u64, LoadResult)>,
+    rx: Receiver<(u64, LoadResult)>,
+    generation: u64,
+
+    // 预取：最多缓存一张已解码的下一张图片（解码失败时缓存错误，切到这一张时再报告）
+    prefetch_tx: Sender<(u64, LoadResult)>,
+    prefetch_rx: Receiver<(u64, LoadResult)>,
+    prefetch_generation: u64,
+    /// 正在预取或已预取完成的文件
+    prefetch_path: Option<PathBuf>,
+    prefetched: Option<LoadResult>,
+    /// 预取尚未完成时用户已经切到了这一张：到达后直接显示
+    prefetch_wanted: bool,
+    /// 由 set_max_texture_dimension / set_max_pixels 设置
+    limits: DecodeLimits,
+}
+
+impl Default for ImageLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageLoader {
+    pub fn new() -> Self {
+        let (tx, rx) = channel();
+        let (prefetch_tx, prefetch_rx) = channel();
+        Self {
+            playlist: None,
+            tx,
+            rx,
+            generation: 0,
+            prefetch_tx,
+            prefetch_rx,
+            prefetch_generation: 0,
+            prefetch_path: None,
+            prefetched: None,
+            prefetch_wanted: false,
+            limits: DecodeLimits {
+                max_texture_dimension: u32::MAX,
+                max_pixels: DEFAULT_MAX_MEGAPIXELS * 1_000_000,
+            },
+        }
+    }
+
+    /// 设置 GPU 纹理边长上限（Renderer::max_texture_dimension），之后解码的图片会在后台缩放
+    pub fn set_max_texture_dimension(&mut self, max: u32) {
+        // 预取的下一张是按旧的上限缩放的
+        if max != self.limits.max_texture_dimension {
+            self.invalidate_prefetch();
+        }
+        self.limits.max_texture_dimension = max;
+    }
+
+    /// 设置解码像素上限（默认 DEFAULT_MAX_MEGAPIXELS 百万像素），超过的图片报“图片过大”而不解码
+    pub fn set_max_pixels(&mut self, max: u64) {
+        self.limits.max_pixels = max;
+    }
+
+    pub fn playlist(&self) -> Option<&Playlist> {
+        self.playlist.as_ref()
+    }
+
+    /// 打开任意文件（对话框 / 拖放）：重建所在文件夹的播放列表
+    pub fn open(&mut self, path: PathBuf) {
+        self.playlist = Some(Playlist::from_file(&path));
+        self.load(path);
+    }
+
+    /// 从标准输入读取一张图片；没有所在文件夹，因此也没有播放列表
+    pub fn open_stdin(&mut self) {
+        self.playlist = None;
+        self.invalidate_prefetch();
+        self.generation += 1;
+
+        let generation = self.generation;
+        let limits = self.limits;
+        let tx = self.tx.clone();
+        thread::spawn(move || {
+            let result = decode_stdin(limits.max_pixels);
+            finish_decode(result, limits, generation, &tx);
+        });
+    }
+
+    /// 打开立方体贴图（六个面）；不属于任何播放列表
+    pub fn open_cubemap(&mut self, faces: [PathBuf; 6]) {
+        self.playlist = None;
+        self.invalidate_prefetch();
+        self.generation += 1;
+
+        let generation = self.generation;
+        let limits = self.limits;
+        let tx = self.tx.clone();
+        thread::spawn(move || {
+            let start = Instant::now();
+            let result = cubemap::decode_faces(&faces, limits.max_pixels).map(|faces| {
+                let elapsed = start.elapsed();
+                LoadedImage {
+                    image: PanoramaImage::Cubemap(Box::new(faces)),
+                    metadata: ImageMetadata::default(),
+                    decode_time: elapsed,
+                    worker_time: elapsed,
+                    coverage: None,
+                    full_resolution: None,
+                    kind: ImageKind::Cubemap,
+                    auto_exposure_ev: 0.0,
+                    view: None,
+                }
+            });
+            finish_decode(result, limits, generation, &tx);
+        });
+    }
+
+    /// 在播放列表中前后移动；只有“下一张”能命中预取，其余跳转都会作废预取
+    /// 返回 false 表示没有可切换的图片
+    pub fn step(&mut self, delta: isize) -> bool {
+        let Some(path) = self.playlist.as_mut().and_then(|p| p.step(delta)) else {
+            return false;
+        };
+        let path = path.to_path_buf();
+
+        if delta == 1 && self.prefetch_path.as_deref() == Some(path.as_path()) {
+            // 让仍在途中的旧普通加载作废
+            self.generation += 1;
+            self.prefetch_wanted = true;
+        } else {
+            self.load(path);
+        }
+        true
+    }
+
+    /// 取出可以显示的图片或加载失败的错误（每帧调用，不阻塞）
+    pub fn poll(&mut self) -> Option<LoadResult> {
+        while let Ok((generation, loaded)) = self.prefetch_rx.try_recv() {
+            if generation == self.prefetch_generation {
+                self.prefetched = Some(loaded);
+            }
+        }
+
+        let ready = if self.prefetch_wanted && self.prefetched.is_some() {
+            self.prefetch_wanted = false;
+            self.prefetch_path = None;
+            self.prefetched.take()
+        } else {
+            let mut ready = None;
+            while let Ok((generation, loaded)) = self.rx.try_recv() {
+                if generation == self.generation {
+                    ready = Some(loaded);
+                }
+            }
+            ready
+        }?;
+
+        // 当前图片就绪后立即开始预取下一张
+        self.prefetch_next();
+        Some(ready)
+    }
+
+    fn load(&mut self, path: PathBuf) {
+        self.invalidate_prefetch();
+        self.generation += 1;
+        spawn_decode(path, self.generation, self.limits, self.tx.clone());
+    }
+
+    fn prefetch_next(&mut self) {
+        self.invalidate_prefetch();
+        let Some(next) = self.playlist.as_ref().and_then(|p| p.peek(1)) else {
+            return;
+        };
+        let next = next.to_path_buf();
+        self.prefetch_path = Some(next.clone());
+        spawn_decode(next, self.prefetch_generation, self.limits, self.prefetch_tx.clone());
+    }
+
+    fn invalidate_prefetch(&mut self) {
+        self.prefetch_generation += 1;
+        self.prefetch_path = None;
+        self.prefetched = None;
+        self.prefetch_wanted = false;
+    }
+}
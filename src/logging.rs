@@ -0,0 +1,108 @@
+// logging.rs — 日志输出：标准错误 + 可选的日志文件
+//
+// 各模块用 log 宏输出（文字仍经 i18n 翻译），由独立程序在启动时初始化 env_logger。
+// Release 版在 Windows 上没有控制台，看不到标准错误，因此默认同时写入日志文件，
+// 用户报告问题时可以附上。嵌入本库的程序可以使用自己的 logger，不必调用这里。
+//
+// - --log-level <off|error|warn|info|debug|trace>：本程序的日志级别，默认 info；
+//   依赖库（wgpu 等）最多输出到 warn，RUST_LOG 可以覆盖这些设置
+// - --log-file [path]：写入日志文件（每次启动覆盖），不带路径时写到 log_dir() 下
+
+use log::LevelFilter;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// 日志文件的默认目录：Windows %LOCALAPPDATA%，macOS ~/Library/Logs，Linux ~/.local/state
+pub fn log_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let dir = dirs::home_dir().map(|h| h.join("Library").join("Logs"));
+    #[cfg(not(target_os = "macos"))]
+    let dir = dirs::state_dir().or_else(dirs::data_local_dir);
+    dir.map(|d| d.join("panorama_viewer"))
+}
+
+/// 同时写到标准错误和日志文件
+struct Tee {
+    file: Option<File>,
+}
+
+impl Write for Tee {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // 没有控制台时写标准错误会失败，不影响文件
+        let _ = std::io::stderr().write_all(buf);
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(buf)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let _ = std::io::stderr().flush();
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 日志文件路径：命令行指定的路径，或 --log-file 不带路径 / Windows Release 版时的默认位置
+fn log_file_path() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1).peekable();
+    while let Some(a) = args.next() {
+        if a == "--log-file" {
+            // 值可省略，规则与 cli::path_from_args 相同
+            return match args.next_if(|v| !v.starts_with("--")) {
+                Some(path) => Some(PathBuf::from(path)),
+                None => log_dir().map(|d| d.join("panorama_viewer.log")),
+            };
+        }
+    }
+    if cfg!(all(windows, not(debug_assertions))) {
+        return log_dir().map(|d| d.join("panorama_viewer.log"));
+    }
+    None
+}
+
+/// 初始化日志；需要在 i18n::init 之后调用，提示文字按当前语言输出
+pub fn init() {
+    let level_arg = crate::cli::value("--log-level");
+    let level = level_arg.as_deref().map(str::parse::<LevelFilter>);
+
+    let path = log_file_path();
+    let (file, file_error) = match path.as_ref().map(|path| {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        File::create(path)
+    }) {
+        Some(Ok(file)) => (Some(file), None),
+        Some(Err(e)) => (None, Some(e)),
+        None => (None, None),
+    };
+    let own_level = match level {
+        Some(Ok(level)) => level,
+        _ => LevelFilter::Info,
+    };
+    env_logger::Builder::new()
+        .filter_level(own_level.min(LevelFilter::Warn))
+        .filter_module(env!("CARGO_CRATE_NAME"), own_level)
+        .parse_default_env()
+        .format_timestamp_millis()
+        .target(env_logger::Target::Pipe(Box::new(Tee { file })))
+        .init();
+
+    if let (Some(Err(_)), Some(text)) = (&level, &level_arg) {
+        crate::cli::warn_invalid("--log-level", text);
+    }
+    if let Some(path) = path {
+        let path = path.display().to_string();
+        match file_error {
+            None => log::info!("{}", crate::i18n::tr_with("log.log_file", &[("path", path)])),
+            Some(e) => log::warn!(
+                "{}",
+                crate::i18n::tr_with("log.log_file_failed", &[("path", path), ("err", e.to_string())])
+            ),
+        }
+    }
+}
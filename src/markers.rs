@@ -0,0 +1,46 @@
+// markers.rs — 图片上的标注点（导览热点）
+//
+// 标注按图片路径保存在 config.json（Config::markers）。方向约定与 yaw/pitch 相同：
+// yaw 向右为正、pitch 向下为正（度）。每帧经当前投影映射到屏幕，由 app.rs 的 draw_markers 绘制。
+
+use crate::projection;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Marker {
+    pub yaw: f32,
+    pub pitch: f32,
+    /// 悬停时显示的文字；没有跳转目标时点击也显示这段文字
+    pub text: String,
+    /// 点击后转到的视角
+    #[serde(default)]
+    pub target: Option<MarkerTarget>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MarkerTarget {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub fov: f32,
+}
+
+impl Marker {
+    pub fn new(yaw: f32, pitch: f32, text: String) -> Self {
+        Self { yaw, pitch, text, target: None }
+    }
+
+    /// 世界坐标系中的方向（单位向量）
+    pub fn direction(&self) -> Vec3 {
+        projection::look_direction(self.yaw.to_radians(), self.pitch.to_radians())
+    }
+}
+
+/// Config::markers 的键：图片的绝对路径（无法规范化时用原路径）
+pub fn image_key(path: &Path) -> String {
+    path.canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
@@ -51,3 +51,87 @@ pub fn build_sphere(radius: f32, lat: usize, lon: usize) -> SphereMesh {
         indices,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADIUS: f32 = 2.5;
+    const LAT: usize = 12;
+    const LON: usize = 24;
+
+    fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+    }
+
+    fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    }
+
+    fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+    }
+
+    #[test]
+    fn counts_match_lat_lon() {
+        for (lat, lon) in [(1, 3), (2, 4), (LAT, LON), (64, 128)] {
+            let mesh = build_sphere(RADIUS, lat, lon);
+            // 接缝与两极的顶点不共享，所以每行每列各多一个
+            assert_eq!(mesh.positions.len(), (lat + 1) * (lon + 1));
+            assert_eq!(mesh.uvs.len(), mesh.positions.len());
+            assert_eq!(mesh.indices.len(), lat * lon * 6);
+        }
+    }
+
+    #[test]
+    fn positions_lie_on_radius() {
+        let mesh = build_sphere(RADIUS, LAT, LON);
+        for p in &mesh.positions {
+            let r = dot(*p, *p).sqrt();
+            assert!((r - RADIUS).abs() < 1e-4, "{:?} has radius {}", p, r);
+        }
+    }
+
+    #[test]
+    fn uvs_in_unit_range_and_flipped() {
+        let mesh = build_sphere(RADIUS, LAT, LON);
+        for uv in &mesh.uvs {
+            assert!((0.0..=1.0).contains(&uv[0]) && (0.0..=1.0).contains(&uv[1]), "{:?}", uv);
+        }
+        // 沿用 JavaFX 版的翻转：第一个顶点（北极、phi=0）对应 (1, 1)，最后一个对应 (0, 0)
+        assert_eq!(mesh.uvs[0], [1.0, 1.0]);
+        assert_eq!(*mesh.uvs.last().unwrap(), [0.0, 0.0]);
+        assert!(mesh.positions[0][1] > 0.0, "first row is the +Y pole");
+    }
+
+    #[test]
+    fn indices_in_range() {
+        let mesh = build_sphere(RADIUS, LAT, LON);
+        let n = mesh.positions.len() as u32;
+        assert!(mesh.indices.iter().all(|&i| i < n));
+    }
+
+    #[test]
+    fn winding_is_consistent() {
+        let mesh = build_sphere(RADIUS, LAT, LON);
+        for tri in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|k| mesh.positions[tri[k] as usize]);
+            let normal = cross(sub(b, a), sub(c, a));
+            // 两极处的三角形退化为线段，跳过
+            if dot(normal, normal).sqrt() < 1e-6 {
+                continue;
+            }
+            let centroid = [
+                (a[0] + b[0] + c[0]) / 3.0,
+                (a[1] + b[1] + c[1]) / 3.0,
+                (a[2] + b[2] + c[2]) / 3.0,
+            ];
+            // 右手法线朝内：相机在球心时三角形为逆时针（正面），无需关闭背面剔除
+            assert!(dot(normal, centroid) < 0.0, "triangle {:?} faces outward", tri);
+        }
+    }
+}
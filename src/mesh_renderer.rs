@@ -0,0 +1,209 @@
+// mesh_renderer.rs — 备用渲染路径：用 mesh.rs 生成的 UV 球体 + 透视相机绘制全景
+//
+// 默认的光线投射（shader_equirect.wgsl）在片元着色器中逐像素计算投影；
+// 这里改用真实的顶点/索引缓冲，相机位于球心，只支持标准透视。
+// 用途：与光线投射的结果对照排查问题，或在片元着色器较慢的老旧 GPU 上使用。
+// 通过 `--renderer mesh` 或菜单 视图 → 渲染方式 选择。
+
+use crate::mesh::build_sphere;
+use crate::projection::{alignment_rotation, camera_rotation, ViewParams};
+use glam::Mat4;
+use wgpu::util::DeviceExt;
+
+/// 球体细分：经线数需为 4 的倍数，使等矩形 UV 的环绕点落在网格边上
+const SPHERE_LAT: usize = 64;
+const SPHERE_LON: usize = 128;
+const NEAR: f32 = 0.01;
+const FAR: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderPath {
+    /// 全屏三角形 + 片元着色器光线投射，支持全部投影模式
+    #[default]
+    RayCast,
+    /// 网格球体 + 透视相机
+    Mesh,
+}
+
+impl RenderPath {
+    /// 解析命令行 `--renderer mesh|raycast`；未指定时返回 None
+    pub fn from_args() -> Option<Self> {
+        let mut it = std::env::args();
+        while let Some(a) = it.next() {
+            if a == "--renderer" {
+                return match it.next().as_deref() {
+                    Some("mesh") => Some(RenderPath::Mesh),
+                    Some("raycast") => Some(RenderPath::RayCast),
+                    other => {
+                        log::warn!(
+                            "{}",
+                            crate::i18n::tr_with(
+                                "log.unknown_renderer",
+                                &[("name", other.unwrap_or_default().to_string())]
+                            )
+                        );
+                        None
+                    }
+                };
+            }
+        }
+        None
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct MeshVertex {
+    position: [f32; 3],
+    uv: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct MeshUniform {
+    view_proj: [[f32; 4]; 4],
+}
+
+pub struct MeshRenderer {
+    pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    index_count: u32,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl MeshRenderer {
+    /// 创建网格与管线；texture_layout 为主渲染器的绑定组 0（相机 + 纹理）
+    pub fn new(
+        device: &wgpu::Device,
+        texture_layout: &wgpu::BindGroupLayout,
+        format: wgpu::TextureFormat,
+    ) -> Result<Self, String> {
+        let sphere = build_sphere(1.0, SPHERE_LAT, SPHERE_LON);
+        let vertices: Vec<MeshVertex> = sphere
+            .positions
+            .iter()
+            .zip(&sphere.uvs)
+            .map(|(&position, &uv)| MeshVertex { position, uv })
+            .collect();
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("sphere_vertex_buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("sphere_index_buffer"),
+            contents: bytemuck::cast_slice(&sphere.indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("mesh_uniform_buffer"),
+            contents: bytemuck::cast_slice(&[MeshUniform { view_proj: Mat4::IDENTITY.to_cols_array_2d() }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let mesh_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("mesh_bind_group_layout"),
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &mesh_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() }],
+            label: Some("mesh_bind_group"),
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Mesh Pipeline Layout"),
+            bind_group_layouts: &[texture_layout, &mesh_layout],
+            push_constant_ranges: &[],
+        });
+
+        // 与主管线一样用 error scope 捕获错误，失败时调用方保持光线投射路径
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("shader_mesh"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shader_mesh.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Mesh Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<MeshVertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                // build_sphere 的三角形从球心看是逆时针（见 mesh.rs 的测试）
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            // 相机在球心，每个方向只会看到一个三角形，不需要深度缓冲
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+        if let Some(err) = pollster::block_on(device.pop_error_scope()) {
+            return Err(err.to_string());
+        }
+
+        Ok(Self {
+            pipeline,
+            vertex_buffer,
+            index_buffer,
+            index_count: sphere.indices.len() as u32,
+            uniform_buffer,
+            bind_group,
+        })
+    }
+
+    /// 按与光线投射相同的相机旋转（含水平校正）写入 view-projection 矩阵
+    pub fn update_camera(&self, queue: &wgpu::Queue, view: &ViewParams) {
+        let rotation = alignment_rotation(view) * camera_rotation(view.yaw, view.pitch);
+        let view_matrix = Mat4::from_mat3(rotation.transpose());
+        let proj = Mat4::perspective_rh(view.fov_rad, view.aspect, NEAR, FAR);
+        let uniform = MeshUniform { view_proj: (proj * view_matrix).to_cols_array_2d() };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    pub fn draw<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>, texture_bind_group: &'a wgpu::BindGroup) {
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, texture_bind_group, &[]);
+        pass.set_bind_group(1, &self.bind_group, &[]);
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        pass.draw_indexed(0..self.index_count, 0, 0..1);
+    }
+}
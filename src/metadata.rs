@@ -0,0 +1,235 @@
+// metadata.rs — 从图片文件读取 EXIF / GPano(XMP) 元数据
+//
+// 仅提取查看器实际用到的字段；任何字段缺失或格式不对都静默忽略。
+
+use std::io::Cursor;
+
+/// UTC 时间（拍摄时刻）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UtcDateTime {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImageMetadata {
+    /// 拍摄时间（已换算到 UTC）
+    pub capture_time: Option<UtcDateTime>,
+    /// GPS 纬度/经度（度，北纬/东经为正）
+    pub gps: Option<(f64, f64)>,
+    /// 图像中心所朝的罗盘方位（度，正北为 0，顺时针）
+    /// 优先 GPano:PoseHeadingDegrees，其次 EXIF GPSImgDirection
+    pub heading_deg: Option<f64>,
+    /// GPano 裁切信息：局部全景在完整等矩形画布中的位置
+    pub crop: Option<GPanoCrop>,
+    /// EXIF Orientation（1..=8，1 为正常）；解码器不处理，由 loader 转正
+    pub orientation: Option<u32>,
+}
+
+/// GPano:CroppedArea* / FullPano*（像素，基于完整全景的尺寸）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GPanoCrop {
+    pub full_width: u32,
+    pub full_height: u32,
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub fn read_metadata(bytes: &[u8]) -> ImageMetadata {
+    let mut meta = ImageMetadata::default();
+
+    if let Ok(exif) = exif::Reader::new().read_from_container(&mut Cursor::new(bytes)) {
+        meta.gps = read_gps(&exif);
+        meta.capture_time = read_gps_time(&exif).or_else(|| read_original_time(&exif, meta.gps));
+        meta.heading_deg = rational(&exif, exif::Tag::GPSImgDirection, 0);
+        meta.orientation = exif
+            .get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+            .and_then(|f| f.value.get_uint(0))
+            .filter(|o| (1..=8).contains(o));
+    }
+
+    if let Some(xmp) = find_xmp_packet(bytes) {
+        if let Some(h) = xmp_value(&xmp, "GPano:PoseHeadingDegrees").and_then(|v| v.parse().ok()) {
+            meta.heading_deg = Some(h);
+        }
+        meta.crop = read_gpano_crop(&xmp);
+    }
+
+    meta
+}
+
+fn rational(exif: &exif::Exif, tag: exif::Tag, index: usize) -> Option<f64> {
+    let field = exif.get_field(tag, exif::In::PRIMARY)?;
+    match &field.value {
+        exif::Value::Rational(v) => v.get(index).map(|r| r.to_f64()),
+        _ => None,
+    }
+}
+
+fn ascii(exif: &exif::Exif, tag: exif::Tag) -> Option<Vec<u8>> {
+    let field = exif.get_field(tag, exif::In::PRIMARY)?;
+    match &field.value {
+        exif::Value::Ascii(v) => v.first().cloned(),
+        _ => None,
+    }
+}
+
+fn dms(exif: &exif::Exif, tag: exif::Tag) -> Option<f64> {
+    let d = rational(exif, tag, 0)?;
+    let m = rational(exif, tag, 1).unwrap_or(0.0);
+    let s = rational(exif, tag, 2).unwrap_or(0.0);
+    Some(d + m / 60.0 + s / 3600.0)
+}
+
+fn read_gps(exif: &exif::Exif) -> Option<(f64, f64)> {
+    let mut lat = dms(exif, exif::Tag::GPSLatitude)?;
+    let mut lon = dms(exif, exif::Tag::GPSLongitude)?;
+    if ascii(exif, exif::Tag::GPSLatitudeRef).as_deref() == Some(b"S") {
+        lat = -lat;
+    }
+    if ascii(exif, exif::Tag::GPSLongitudeRef).as_deref() == Some(b"W") {
+        lon = -lon;
+    }
+    Some((lat, lon))
+}
+
+/// GPSDateStamp + GPSTimeStamp 本身就是 UTC
+fn read_gps_time(exif: &exif::Exif) -> Option<UtcDateTime> {
+    let date = ascii(exif, exif::Tag::GPSDateStamp)?;
+    let date = std::str::from_utf8(&date).ok()?;
+    let mut parts = date.split(':');
+    let year = parts.next()?.trim().parse().ok()?;
+    let month = parts.next()?.trim().parse().ok()?;
+    let day = parts.next()?.trim().parse().ok()?;
+
+    Some(UtcDateTime {
+        year,
+        month,
+        day,
+        hour: rational(exif, exif::Tag::GPSTimeStamp, 0)? as u32,
+        minute: rational(exif, exif::Tag::GPSTimeStamp, 1)? as u32,
+        second: rational(exif, exif::Tag::GPSTimeStamp, 2)?,
+    })
+}
+
+/// DateTimeOriginal 是本地时间：有 OffsetTimeOriginal 时按其换算；
+/// 否则用经度粗略估计时区（每 15° 一小时），误差在太阳方位上通常只有几度。
+fn read_original_time(exif: &exif::Exif, gps: Option<(f64, f64)>) -> Option<UtcDateTime> {
+    let raw = ascii(exif, exif::Tag::DateTimeOriginal)?;
+    let mut dt = exif::DateTime::from_ascii(&raw).ok()?;
+
+    let offset_minutes = match ascii(exif, exif::Tag::OffsetTimeOriginal) {
+        Some(offset) if dt.parse_offset(&offset).is_ok() => dt.offset.map(f64::from),
+        _ => None,
+    }
+    .or_else(|| gps.map(|(_, lon)| (lon / 15.0).round() * 60.0))?;
+
+    let local = UtcDateTime {
+        year: i32::from(dt.year),
+        month: u32::from(dt.month),
+        day: u32::from(dt.day),
+        hour: u32::from(dt.hour),
+        minute: u32::from(dt.minute),
+        second: f64::from(dt.second),
+    };
+    Some(local.shifted_minutes(-offset_minutes))
+}
+
+impl UtcDateTime {
+    /// 自 1970-01-01 起的天数（含小数）
+    pub fn unix_days(&self) -> f64 {
+        let days = days_from_civil(self.year, self.month, self.day) as f64;
+        days + (self.hour as f64 + self.minute as f64 / 60.0 + self.second / 3600.0) / 24.0
+    }
+
+    fn shifted_minutes(&self, minutes: f64) -> Self {
+        let t = self.unix_days() + minutes / 1440.0;
+        let day = t.floor();
+        let (year, month, d) = civil_from_days(day as i64);
+        let secs = (t - day) * 86400.0;
+        Self {
+            year,
+            month,
+            day: d,
+            hour: (secs / 3600.0) as u32,
+            minute: ((secs % 3600.0) / 60.0) as u32,
+            second: secs % 60.0,
+        }
+    }
+}
+
+// Howard Hinnant 的 days_from_civil / civil_from_days（公历 <-> 天数）
+fn days_from_civil(y: i32, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y as i64 - 1 } else { y as i64 };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(z: i64) -> (i32, u32, u32) {
+    let z = z + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y as i32, m, d)
+}
+
+fn read_gpano_crop(xmp: &str) -> Option<GPanoCrop> {
+    let px = |name: &str| -> Option<u32> {
+        xmp_value(xmp, &format!("GPano:{}", name))?.parse().ok()
+    };
+    let crop = GPanoCrop {
+        full_width: px("FullPanoWidthPixels")?,
+        full_height: px("FullPanoHeightPixels")?,
+        left: px("CroppedAreaLeftPixels").unwrap_or(0),
+        top: px("CroppedAreaTopPixels").unwrap_or(0),
+        width: px("CroppedAreaImageWidthPixels")?,
+        height: px("CroppedAreaImageHeightPixels")?,
+    };
+    let valid = crop.width > 0
+        && crop.height > 0
+        && crop.width <= crop.full_width
+        && crop.top + crop.height <= crop.full_height;
+    valid.then_some(crop)
+}
+
+/// 在文件字节中查找 XMP 包（<x:xmpmeta ...> ... </x:xmpmeta>）
+fn find_xmp_packet(bytes: &[u8]) -> Option<String> {
+    let start = find_bytes(bytes, b"<x:xmpmeta")?;
+    let end_tag = b"</x:xmpmeta>";
+    let end = find_bytes(&bytes[start..], end_tag)? + start + end_tag.len();
+    Some(String::from_utf8_lossy(&bytes[start..end]).into_owned())
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// 读取 XMP 属性值，兼容两种写法：`Name="value"` 与 `<Name>value</Name>`
+fn xmp_value(xmp: &str, name: &str) -> Option<String> {
+    let attr = format!("{}=\"", name);
+    if let Some(i) = xmp.find(&attr) {
+        let rest = &xmp[i + attr.len()..];
+        return rest.find('"').map(|j| rest[..j].trim().to_string());
+    }
+
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let i = xmp.find(&open)? + open.len();
+    let j = xmp[i..].find(&close)? + i;
+    Some(xmp[i..j].trim().to_string())
+}
@@ -0,0 +1,72 @@
+// motion.rs — “减少动态效果”：关闭视角过渡动画与越界回弹，所有视角变化立即生效
+//
+// config.json 的 reduce_motion 取 "system" / "on" / "off"，默认跟随系统的辅助功能设置：
+// - Windows：“在 Windows 中显示动画”（HKCU\Control Panel\Desktop\WindowMetrics 的 MinAnimate）
+// - macOS：辅助功能中的“减弱动态效果”（com.apple.universalaccess reduceMotion）
+// - Linux：GNOME 的 org.gnome.desktop.interface enable-animations
+// 查询不到时视为未开启；系统设置只在第一次用到时读取，运行中修改需要重启程序。
+// 所有带动画的地方（视角过渡、越界回弹、界面的展开动画）都只看 PanoramaViewer3D::reduce_motion，
+// 由主循环每帧按 ReduceMotion::enabled 设置。
+
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReduceMotion {
+    /// 跟随系统设置
+    #[default]
+    System,
+    On,
+    Off,
+}
+
+impl ReduceMotion {
+    pub fn enabled(self) -> bool {
+        match self {
+            ReduceMotion::System => system_prefers_reduced_motion(),
+            ReduceMotion::On => true,
+            ReduceMotion::Off => false,
+        }
+    }
+}
+
+/// 系统是否要求减少动画；第一次调用时查询，之后使用缓存的结果
+pub fn system_prefers_reduced_motion() -> bool {
+    static SYSTEM: OnceCell<bool> = OnceCell::new();
+    *SYSTEM.get_or_init(|| query_system().unwrap_or(false))
+}
+
+/// 运行命令并返回去掉首尾空白的标准输出；命令不存在或执行失败时返回 None
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW：Release 版没有控制台，避免闪出命令行窗口
+        command.creation_flags(0x0800_0000);
+    }
+    let output = command.output().ok().filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(windows)]
+fn query_system() -> Option<bool> {
+    // 输出的最后一行形如 "    MinAnimate    REG_SZ    0"
+    let text = command_output("reg", &["query", r"HKCU\Control Panel\Desktop\WindowMetrics", "/v", "MinAnimate"])?;
+    Some(text.split_whitespace().last()? == "0")
+}
+
+#[cfg(target_os = "macos")]
+fn query_system() -> Option<bool> {
+    let text = command_output("defaults", &["read", "com.apple.universalaccess", "reduceMotion"])?;
+    Some(text == "1")
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn query_system() -> Option<bool> {
+    let text = command_output("gsettings", &["get", "org.gnome.desktop.interface", "enable-animations"])?;
+    Some(text == "false")
+}
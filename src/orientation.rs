@@ -0,0 +1,140 @@
+// orientation.rs — 设备姿态（加速度计 + 陀螺仪）控制视角
+//
+// 目前只支持 Linux 的 IIO 传感器（/sys/bus/iio/devices，常见于二合一笔记本/平板）；
+// 其它平台 `is_supported()` 返回 false，菜单项不可用，代码照常编译。
+//
+// 设备坐标与相机坐标一致：x 向右、y 沿屏幕向上、视线方向（屏幕背面）为 -z。
+// - pitch 由重力方向求出（绝对值）
+// - yaw 由陀螺仪绕重力轴的角速度积分（相对值，没有磁力计无法得到真北）
+// 渲染器没有 roll，设备的横滚角被忽略。
+
+use std::sync::mpsc::Receiver;
+
+/// 一次传感器读数换算出的相机角度（度，约定与 PanoramaViewer3D 相同）
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceAngles {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+pub struct DeviceOrientation {
+    rx: Receiver<DeviceAngles>,
+    last: Option<DeviceAngles>,
+}
+
+impl DeviceOrientation {
+    /// 开始读取传感器；不支持时返回 None
+    pub fn start() -> Option<Self> {
+        backend::start().map(|rx| Self { rx, last: None })
+    }
+
+    /// 自上次调用以来的变化量 (dyaw, dpitch)。
+    /// 只叠加增量，鼠标拖拽仍然可以在此基础上自由调整。
+    pub fn take_delta(&mut self) -> Option<(f32, f32)> {
+        let latest = self.rx.try_iter().last()?;
+        let delta = self
+            .last
+            .map(|prev| (latest.yaw - prev.yaw, latest.pitch - prev.pitch));
+        self.last = Some(latest);
+        delta
+    }
+}
+
+pub fn is_supported() -> bool {
+    backend::is_supported()
+}
+
+#[cfg(target_os = "linux")]
+mod backend {
+    use super::DeviceAngles;
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc::{channel, Receiver};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    const IIO_DIR: &str = "/sys/bus/iio/devices";
+
+    fn read_f32(path: &Path) -> Option<f32> {
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    /// 读取 `in_{kind}_{x,y,z}_raw` 并乘以缩放系数
+    fn read_vec3(dir: &Path, kind: &str) -> Option<[f32; 3]> {
+        let shared_scale = read_f32(&dir.join(format!("in_{}_scale", kind)));
+        let mut v = [0.0; 3];
+        for (i, axis) in ["x", "y", "z"].iter().enumerate() {
+            let raw = read_f32(&dir.join(format!("in_{}_{}_raw", kind, axis)))?;
+            let scale = read_f32(&dir.join(format!("in_{}_{}_scale", kind, axis)))
+                .or(shared_scale)
+                .unwrap_or(1.0);
+            v[i] = raw * scale;
+        }
+        Some(v)
+    }
+
+    fn find_device(kind: &str) -> Option<PathBuf> {
+        std::fs::read_dir(IIO_DIR)
+            .ok()?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .find(|dir| read_vec3(dir, kind).is_some())
+    }
+
+    pub fn is_supported() -> bool {
+        find_device("accel").is_some()
+    }
+
+    pub fn start() -> Option<Receiver<DeviceAngles>> {
+        let accel_dir = find_device("accel")?;
+        let gyro_dir = find_device("anglvel");
+        let (tx, rx) = channel();
+
+        thread::spawn(move || {
+            let mut yaw = 0.0f32;
+            let mut last = Instant::now();
+            loop {
+                thread::sleep(Duration::from_millis(16));
+                let dt = last.elapsed().as_secs_f32();
+                last = Instant::now();
+
+                let Some(a) = read_vec3(&accel_dir, "accel") else {
+                    continue;
+                };
+                let len = (a[0] * a[0] + a[1] * a[1] + a[2] * a[2]).sqrt();
+                if len < 1e-3 {
+                    continue;
+                }
+                // 静止时加速度计读数指向“上”
+                let up = [a[0] / len, a[1] / len, a[2] / len];
+
+                // 视线 (0,0,-1) 与“上”的夹角给出仰角；pitch 正值为向下看
+                let pitch = up[2].clamp(-1.0, 1.0).asin().to_degrees();
+
+                // 绕竖直轴的角速度（rad/s）：向右转为负，yaw 向右为正
+                if let Some(w) = gyro_dir.as_deref().and_then(|d| read_vec3(d, "anglvel")) {
+                    let w_up = w[0] * up[0] + w[1] * up[1] + w[2] * up[2];
+                    yaw -= (w_up * dt).to_degrees();
+                }
+
+                if tx.send(DeviceAngles { yaw, pitch }).is_err() {
+                    break; // 已关闭
+                }
+            }
+        });
+
+        Some(rx)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod backend {
+    use super::DeviceAngles;
+    use std::sync::mpsc::Receiver;
+
+    pub fn is_supported() -> bool {
+        false
+    }
+
+    pub fn start() -> Option<Receiver<DeviceAngles>> {
+        None
+    }
+}
@@ -1,6 +1,28 @@
 // panorama.rs — 视角参数与投影模式
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+use crate::deeplink::{self, DeepLink};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// 启动时与“重置视图”使用的 FOV（度）
+const DEFAULT_FOV: f32 = 46.8;
+
+/// 视角过渡动画时长
+const CAMERA_MOVE_DURATION: Duration = Duration::from_millis(400);
+
+/// 拖出有效区域时的阻尼（越界部分只跟随鼠标的这一比例）
+const BOUNDS_RESISTANCE: f32 = 0.3;
+/// 松开鼠标后回弹到有效区域的速度（每秒）
+const BOUNDS_SETTLE_RATE: f32 = 12.0;
+
+/// PageUp / PageDown 依次切换的全画幅等效焦距（mm）；DEFAULT_FOV 即 50mm
+pub const FOCAL_PRESETS_MM: [u32; 6] = [14, 24, 35, 50, 85, 135];
+/// 当前焦距与预设相差不超过这个值（mm）时视为正处于该预设
+const FOCAL_PRESET_TOLERANCE_MM: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ProjectionMode {
     Rectilinear,    // 1. 标准透视 (适合正常视角，直线保持直线)
     Equidistant,    // 2. 等距鱼眼 (适合广角，边缘压缩，直线弯曲)
@@ -10,6 +32,99 @@ pub enum ProjectionMode {
     Architectural,  // 6. 建筑校正 (类似 Rectilinear 但修正垂直透视)
 }
 
+/// 按源图判断的图片类型；每种类型记住上次使用的投影（见 Config::projection_by_kind）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageKind {
+    /// 约 2:1 或带 GPano 信息的全景
+    Equirectangular,
+    /// 普通照片
+    Flat,
+    /// 接近正方形，按圆形鱼眼原片处理
+    Fisheye,
+    Cubemap,
+}
+
+impl ImageKind {
+    /// 由原始尺寸（补齐画布之前）判断；带 GPano 裁切信息的一律视为全景
+    pub fn classify(width: u32, height: u32, has_gpano: bool) -> Self {
+        let aspect = width as f32 / height.max(1) as f32;
+        if has_gpano || (1.9..=2.1).contains(&aspect) {
+            ImageKind::Equirectangular
+        } else if (0.9..=1.1).contains(&aspect) {
+            ImageKind::Fisheye
+        } else {
+            ImageKind::Flat
+        }
+    }
+}
+
+impl ProjectionMode {
+    /// 菜单与 P 键循环切换的顺序
+    pub const ALL: [ProjectionMode; 6] = [
+        ProjectionMode::Rectilinear,
+        ProjectionMode::Equidistant,
+        ProjectionMode::Stereographic,
+        ProjectionMode::Pannini,
+        ProjectionMode::Architectural,
+        ProjectionMode::Equirectangular,
+    ];
+
+    /// 切换到该模式时默认使用的 FOV（度）：每种投影在各自擅长的视角下效果最好
+    pub fn default_fov(self) -> f32 {
+        match self {
+            ProjectionMode::Rectilinear => 70.0,
+            ProjectionMode::Equidistant => 120.0,
+            ProjectionMode::Stereographic => 140.0,
+            ProjectionMode::Pannini => 100.0,
+            ProjectionMode::Equirectangular => 90.0,
+            ProjectionMode::Architectural => 70.0,
+        }
+    }
+
+    /// 默认允许的 FOV 范围（度），可在 FOV 范围窗口中按模式修改（见 PanoramaViewer3D::fov_range）
+    pub fn default_fov_range(self) -> (f32, f32) {
+        match self {
+            ProjectionMode::Rectilinear | ProjectionMode::Pannini | ProjectionMode::Architectural => (5.0, 179.9),
+            ProjectionMode::Stereographic => (10.0, 180.0),
+            ProjectionMode::Equidistant | ProjectionMode::Equirectangular => (5.0, 180.0),
+        }
+    }
+
+    /// FOV 的硬性上下限（度），自定义范围也不能超出：
+    /// Rectilinear / Pannini / Architectural 在 shader 中要算 tan(fov/2)，180° 落在奇点。
+    /// PanoramaViewer3D::clamp_fov 在输入时就按它夹取；ViewParams::new 的同一夹取只是兜底
+    pub fn hard_fov_range(self) -> (f32, f32) {
+        match self {
+            ProjectionMode::Rectilinear | ProjectionMode::Pannini | ProjectionMode::Architectural => (1.0, 179.9),
+            _ => (1.0, 180.0),
+        }
+    }
+
+    /// 命令行 `--projection` 使用的名称（与配置文件中的写法相同）
+    pub fn cli_name(self) -> &'static str {
+        match self {
+            ProjectionMode::Rectilinear => "rectilinear",
+            ProjectionMode::Equidistant => "equidistant",
+            ProjectionMode::Stereographic => "stereographic",
+            ProjectionMode::Pannini => "pannini",
+            ProjectionMode::Equirectangular => "equirectangular",
+            ProjectionMode::Architectural => "architectural",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.cli_name() == name)
+    }
+
+    /// ALL 中相邻的模式（delta 为 ±1，循环）
+    pub fn cycle(self, delta: isize) -> Self {
+        let n = Self::ALL.len() as isize;
+        let i = Self::ALL.iter().position(|&m| m == self).unwrap_or(0) as isize;
+        Self::ALL[(i + delta).rem_euclid(n) as usize]
+    }
+}
+
 pub struct PanoramaViewer3D {
     pub yaw: f32,
     pub pitch: f32,
@@ -17,6 +132,83 @@ pub struct PanoramaViewer3D {
     pub sensitivity_scale: f32,
     pub projection_mode: ProjectionMode,
     pub is_fullscreen: bool,
+    // pitch 限位（度，默认 ±90°）；over_the_pole 为 true 时允许越过天顶/天底连续旋转
+    pub pitch_limit: f32,
+    pub over_the_pole: bool,
+    // 正北对应的 yaw（度）；图片带朝向元数据时由其换算，否则为 0（图像中心视为正北）
+    pub yaw_offset: f32,
+    // 局部全景中有实际内容的区域；None 表示完整 360×180
+    pub coverage: Option<Coverage>,
+    // 平面展开模式下中键拖动的平移量（度，x 向右、y 向下）；离开该模式时并入 yaw/pitch
+    pub pan: [f32; 2],
+    // 水平校正（度，[pitch, roll]）：拍摄时没有放平的全景整体转回水平，只在本次查看中有效
+    pub alignment: [f32; 2],
+    // 用户自定义的 FOV 范围（度，[min, max]），覆盖 default_fov_range；没有列出的模式使用默认值
+    pub fov_limits: BTreeMap<ProjectionMode, [f32; 2]>,
+    // 减少动态效果：animate_to 直接跳到目标，越界时立即拉回（见 motion.rs）
+    pub reduce_motion: bool,
+    animation: Option<CameraMove>,
+}
+
+/// 全景图中有实际内容的范围（度，约定与 yaw/pitch 相同：yaw 向右为正，pitch 向下为正）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coverage {
+    /// 水平范围 (min, max)；None 表示水平方向完整 360°
+    pub yaw: Option<(f32, f32)>,
+    /// 垂直范围 (min, max)，完整时为 (-90, 90)
+    pub pitch: (f32, f32),
+    /// 纹理没有补齐到 2:1 画布，只包含 pitch 范围内的行（极宽的条幅全景，见 resample::prepare_equirect）
+    pub strip: bool,
+}
+
+/// 把 [lo, hi] 收缩 half 之后的区间；收缩后为空时退化为中点
+fn shrink(range: (f32, f32), half: f32) -> (f32, f32) {
+    let (lo, hi) = (range.0 + half, range.1 - half);
+    if lo <= hi {
+        (lo, hi)
+    } else {
+        let mid = (range.0 + range.1) * 0.5;
+        (mid, mid)
+    }
+}
+
+/// yaw 是连续值：返回 360° 的整数倍，使 yaw 减去它后最接近 range 的中心
+fn wrap_shift(yaw: f32, range: (f32, f32)) -> f32 {
+    let mid = (range.0 + range.1) * 0.5;
+    ((yaw - mid) / 360.0).round() * 360.0
+}
+
+/// 视角过渡动画（跳转方向、重置等共用一条缓动曲线）
+#[derive(Debug, Clone, Copy)]
+struct CameraMove {
+    from: [f32; 3],
+    to: [f32; 3],
+    start: Instant,
+}
+
+/// 角度保留一位小数，整数时省略小数部分（30.0 → "30"）
+fn format_deg(deg: f32) -> String {
+    // 加 0.0 把 -0.0 变成 0.0，避免输出 "-0"
+    let text = format!("{:.1}", (deg * 10.0).round() / 10.0 + 0.0);
+    match text.strip_suffix(".0") {
+        Some(int) => int.to_string(),
+        None => text,
+    }
+}
+
+/// 三次缓入缓出
+fn ease_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+impl Default for PanoramaViewer3D {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PanoramaViewer3D {
@@ -24,10 +216,441 @@ impl PanoramaViewer3D {
         Self {
             yaw: 0.0,
             pitch: 0.0,
-            fov: 46.8,
+            fov: DEFAULT_FOV,
             sensitivity_scale: 1.0,
             projection_mode: ProjectionMode::Rectilinear,
             is_fullscreen: false,
+            pitch_limit: 90.0,
+            over_the_pole: false,
+            yaw_offset: 0.0,
+            coverage: None,
+            pan: [0.0; 2],
+            alignment: [0.0; 2],
+            fov_limits: BTreeMap::new(),
+            reduce_motion: false,
+            animation: None,
         }
     }
+
+    /// 以缓动动画移动到目标视角；yaw 走最短的方向。减少动态效果时直接跳到目标
+    pub fn animate_to(&mut self, yaw: f32, pitch: f32, fov: f32) {
+        let yaw = self.yaw + (yaw - self.yaw + 180.0).rem_euclid(360.0) - 180.0;
+        let fov = self.clamp_fov(fov);
+        if self.reduce_motion {
+            self.animation = None;
+            (self.yaw, self.pitch, self.fov) = (yaw, pitch, fov);
+            return;
+        }
+        self.animation = Some(CameraMove {
+            from: [self.yaw, self.pitch, self.fov],
+            to: [yaw, pitch, fov],
+            start: Instant::now(),
+        });
+    }
+
+    /// 切换投影模式；keep_fov 为 false 时 FOV 缓动到新模式的默认值
+    pub fn set_projection_mode(&mut self, mode: ProjectionMode, keep_fov: bool) {
+        if mode == self.projection_mode {
+            return;
+        }
+        // 平移只在平面展开中生效，切走时并入视线方向，画面中心保持不变
+        self.yaw += self.pan[0];
+        self.pitch += self.pan[1];
+        self.pan = [0.0; 2];
+        self.projection_mode = mode;
+        if keep_fov {
+            // 保留的 FOV 可能超出新投影的范围（如从鱼眼的 180° 切到透视）
+            self.fov = self.clamp_fov(self.fov);
+        } else {
+            self.animate_to(self.yaw, self.pitch, self.default_fov());
+        }
+    }
+
+    /// 当前投影允许的 FOV 范围（度）：自定义范围（见 fov_limits）或默认范围，
+    /// 自定义值仍夹取到 hard_fov_range 之内
+    pub fn fov_range(&self) -> (f32, f32) {
+        let mode = self.projection_mode;
+        let (lo, hi) = mode.hard_fov_range();
+        match self.fov_limits.get(&mode) {
+            Some(&[min, max]) => {
+                let min = min.clamp(lo, hi);
+                (min, max.clamp(min, hi))
+            }
+            None => mode.default_fov_range(),
+        }
+    }
+
+    /// 夹取到当前投影的 fov_range 之内。写入 fov 的地方（缩放、动画、切换投影、命令行等）都经过这里，
+    /// fov 始终是实际渲染的值，状态栏不会显示 ViewParams::new 安全夹取之前的数字（如透视模式下的 180°）
+    pub fn clamp_fov(&self, fov: f32) -> f32 {
+        let (min_fov, max_fov) = self.fov_range();
+        fov.clamp(min_fov, max_fov)
+    }
+
+    /// 当前投影的默认 FOV，夹取到 fov_range 之内
+    pub fn default_fov(&self) -> f32 {
+        self.clamp_fov(self.projection_mode.default_fov())
+    }
+
+    /// “显示全景”：把 FOV 放到当前投影的上限
+    /// 小行星模式同时俯视天底，整个星球（天底半球）正好落在画面内；平面展开回到垂直居中
+    pub fn fit_to_extents(&mut self) {
+        self.pan = [0.0; 2];
+        let pitch = match self.projection_mode {
+            ProjectionMode::Stereographic => 90.0,
+            ProjectionMode::Equirectangular => 0.0,
+            _ => self.pitch,
+        };
+        self.animate_to(self.yaw, pitch, self.fov_range().1);
+    }
+
+    /// 回到初始视角
+    pub fn reset_view(&mut self) {
+        self.pan = [0.0; 2];
+        self.animate_to(0.0, 0.0, DEFAULT_FOV);
+    }
+
+    /// 缩小 FOV（放大画面）delta 度，负值为缩小画面；夹取到当前投影的范围
+    pub fn zoom_by(&mut self, delta: f32) {
+        self.stop_animation();
+        self.fov = self.clamp_fov(self.fov - delta);
+    }
+
+    /// 按 FOCAL_PRESETS_MM 切换到下一个更长（delta > 0）或更短的等效焦距，FOV 缓动过去；
+    /// 没有更远的预设或换算出的 FOV 超出当前投影的范围时不动。平面展开没有焦距的概念，忽略
+    pub fn step_focal_preset(&mut self, delta: isize) {
+        if self.projection_mode == ProjectionMode::Equirectangular || delta == 0 {
+            return;
+        }
+        // 动画进行中连续按键时从目标值继续走，而不是从中间值
+        let fov = self.animation.map_or(self.fov, |anim| anim.to[2]);
+        let current = crate::projection::equivalent_focal_mm(fov);
+        let next = if delta > 0 {
+            FOCAL_PRESETS_MM.into_iter().find(|&f| f as f32 > current + FOCAL_PRESET_TOLERANCE_MM)
+        } else {
+            FOCAL_PRESETS_MM.into_iter().rev().find(|&f| (f as f32) < current - FOCAL_PRESET_TOLERANCE_MM)
+        };
+        let (min_fov, max_fov) = self.fov_range();
+        if let Some(target) = next.map(|f| crate::projection::fov_for_focal_mm(f as f32)) {
+            if (min_fov..=max_fov).contains(&target) {
+                let (yaw, pitch) = self.animation.map_or((self.yaw, self.pitch), |anim| (anim.to[0], anim.to[1]));
+                self.animate_to(yaw, pitch, target);
+            }
+        }
+    }
+
+    /// 当前 FOV 正好对应的焦距预设（mm），供状态栏显示
+    pub fn focal_preset(&self) -> Option<u32> {
+        if self.projection_mode == ProjectionMode::Equirectangular {
+            return None;
+        }
+        let current = crate::projection::equivalent_focal_mm(self.fov);
+        FOCAL_PRESETS_MM.into_iter().find(|&f| (f as f32 - current).abs() <= FOCAL_PRESET_TOLERANCE_MM)
+    }
+
+    /// FOV 缓动回当前投影的默认值，视线方向不变
+    pub fn reset_zoom(&mut self) {
+        self.animate_to(self.yaw, self.pitch, self.default_fov());
+    }
+
+    /// 平面展开模式下平移图片（度，x 向右、y 向下）；垂直方向保证画面中心仍在图片内
+    pub fn pan_by(&mut self, dx_deg: f32, dy_deg: f32) {
+        self.pan[0] = (self.pan[0] + dx_deg + 180.0).rem_euclid(360.0) - 180.0;
+        self.pan[1] = (self.pitch + self.pan[1] + dy_deg).clamp(-90.0, 90.0) - self.pitch;
+    }
+
+    /// 与渲染器一致的投影参数（含平面展开的平移），供叠加层和取色器使用
+    pub fn view_params(&self, aspect: f32) -> crate::projection::ViewParams {
+        crate::projection::ViewParams::new(self.yaw, self.pitch, self.fov, self.projection_mode, aspect)
+            .with_pan(self.pan)
+            .with_alignment(self.alignment)
+    }
+
+    /// 当前相机姿态（旋转基 / 四元数）与投影参数，供嵌入程序同步其它视图或叠加 3D 内容
+    pub fn view_pose(&self, aspect: f32) -> crate::projection::ViewPose {
+        crate::projection::ViewPose::new(self.view_params(aspect))
+    }
+
+    /// 画面中心的方向 (yaw, pitch)（度）；平面展开的平移并入视线方向，与切换投影时的处理一致
+    pub fn view_center(&self) -> (f32, f32) {
+        (self.yaw + self.pan[0], self.pitch + self.pan[1])
+    }
+
+    /// 画面中心在源图中的方向 (yaw, pitch)（度）：view_center 再计入水平校正，
+    /// 用于标注这类附着在图片内容上的位置
+    pub fn image_center(&self) -> (f32, f32) {
+        let (yaw, pitch) = self.view_center();
+        let dir = crate::projection::look_direction(yaw.to_radians(), pitch.to_radians());
+        let align = crate::projection::alignment_rotation(&self.view_params(1.0));
+        let (yaw, pitch) = crate::projection::direction_yaw_pitch(align * dir);
+        (yaw.to_degrees(), pitch.to_degrees())
+    }
+
+    /// 复现当前视角的命令行参数，如 `--yaw 30 --pitch -10 --fov 70 --projection pannini`
+    pub fn cli_args(&self) -> String {
+        let (yaw, pitch) = self.view_center();
+        let yaw = (yaw + 180.0).rem_euclid(360.0) - 180.0;
+        format!(
+            "--yaw {} --pitch {} --fov {} --projection {}",
+            format_deg(yaw),
+            format_deg(pitch),
+            format_deg(self.fov),
+            self.projection_mode.cli_name()
+        )
+    }
+
+    /// 应用命令行中的 --projection / --fov / --yaw / --pitch；返回是否指定了投影
+    pub fn apply_cli_args(&mut self) -> bool {
+        let projection = crate::cli::value("--projection").and_then(|name| {
+            let mode = ProjectionMode::from_cli_name(&name);
+            if mode.is_none() {
+                crate::cli::warn_invalid("--projection", &name);
+            }
+            mode
+        });
+        if let Some(mode) = projection {
+            self.projection_mode = mode;
+            self.fov = self.default_fov();
+        }
+        if let Some(fov) = crate::cli::number("--fov") {
+            self.fov = self.clamp_fov(fov);
+        }
+        if let Some(yaw) = crate::cli::number("--yaw") {
+            self.yaw = yaw;
+        }
+        if let Some(pitch) = crate::cli::number("--pitch") {
+            self.pitch = self.constrain_pitch(pitch);
+        }
+        projection.is_some()
+    }
+
+    /// 当前视角的深链接查询串（格式见 deeplink.rs）；link 提供图片路径与画面调整
+    pub fn to_query(&self, link: &DeepLink) -> String {
+        let mut pairs = Vec::new();
+        if let Some(image) = &link.image {
+            pairs.push(("image", deeplink::encode(image)));
+        }
+        pairs.push(("yaw", self.yaw.to_string()));
+        pairs.push(("pitch", self.pitch.to_string()));
+        pairs.push(("fov", self.fov.to_string()));
+        pairs.push(("projection", self.projection_mode.cli_name().to_string()));
+        let [tilt, roll] = self.alignment;
+        for (key, value) in [("tilt", tilt), ("roll", roll), ("ev", link.exposure_ev)] {
+            if value != 0.0 {
+                pairs.push((key, value.to_string()));
+            }
+        }
+        if self.pan != [0.0; 2] {
+            pairs.push(("pan", format!("{},{}", self.pan[0], self.pan[1])));
+        }
+        for (key, value) in [("vignette", link.vignette), ("seam", link.seam_blend_deg)] {
+            if let Some(value) = value {
+                pairs.push((key, value.to_string()));
+            }
+        }
+        pairs.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join("&")
+    }
+
+    /// 应用深链接（完整链接或查询串）中的视角，返回其中的图片路径与画面调整；
+    /// 没有列出的字段保持不变，无法识别的 key 或数值输出提示后忽略
+    pub fn from_query(&mut self, query: &str) -> DeepLink {
+        let mut link = DeepLink::default();
+        let mut camera = [None; 3];
+        let mut pan = None;
+        for (key, value) in deeplink::parse_pairs(deeplink::query_part(query)) {
+            let finite = |text: &str| text.parse::<f32>().ok().filter(|v| v.is_finite());
+            let number = || {
+                let number = finite(&value);
+                if number.is_none() {
+                    crate::cli::warn_invalid(&key, &value);
+                }
+                number
+            };
+            match key.as_str() {
+                "image" => link.image = Some(value.clone()),
+                "yaw" => camera[0] = number(),
+                "pitch" => camera[1] = number(),
+                "fov" => camera[2] = number(),
+                "tilt" => self.alignment[0] = number().unwrap_or(self.alignment[0]),
+                "roll" => self.alignment[1] = number().unwrap_or(self.alignment[1]),
+                "ev" => link.exposure_ev = number().unwrap_or(0.0),
+                "vignette" => link.vignette = number(),
+                "seam" => link.seam_blend_deg = number(),
+                "pan" => {
+                    pan = value.split_once(',').and_then(|(x, y)| Some([finite(x)?, finite(y)?]));
+                    if pan.is_none() {
+                        crate::cli::warn_invalid(&key, &value);
+                    }
+                }
+                "projection" => match ProjectionMode::from_cli_name(&value) {
+                    Some(mode) => {
+                        self.projection_mode = mode;
+                        self.fov = self.default_fov();
+                    }
+                    None => crate::cli::warn_invalid(&key, &value),
+                },
+                _ => log::warn!("{}", crate::i18n::tr_with("log.deeplink_unknown_key", &[("key", key.clone())])),
+            }
+        }
+
+        // 投影先于 FOV，FOV 先于 pitch：夹取范围依次取决于前者
+        self.animation = None;
+        if let Some(fov) = camera[2] {
+            self.fov = self.clamp_fov(fov);
+        }
+        if let Some(yaw) = camera[0] {
+            self.yaw = yaw;
+        }
+        if let Some(pitch) = camera[1] {
+            self.pitch = self.constrain_pitch(pitch);
+        }
+        // 平移只在平面展开中生效；原样保存以保证往返无损，只在露出图片上下边缘时夹取
+        self.pan = pan.filter(|_| self.projection_mode == ProjectionMode::Equirectangular).unwrap_or([0.0; 2]);
+        if (self.pitch + self.pan[1]).abs() > 90.0 {
+            self.pan[1] = (self.pitch + self.pan[1]).clamp(-90.0, 90.0) - self.pitch;
+        }
+        link
+    }
+
+    /// 所有相机设置（投影、灵敏度、pitch 限位等）恢复默认；
+    /// 来自当前图片的 yaw_offset / coverage、窗口的全屏状态与减少动态效果的设置保持不变
+    pub fn reset_all(&mut self) {
+        *self = Self {
+            yaw_offset: self.yaw_offset,
+            coverage: self.coverage,
+            is_fullscreen: self.is_fullscreen,
+            reduce_motion: self.reduce_motion,
+            ..Self::new()
+        };
+    }
+
+    /// 转到罗盘方位（0=北、90=东、180=南、270=西），考虑 yaw_offset
+    pub fn snap_to_heading(&mut self, compass_deg: f32) {
+        self.animate_to(self.yaw_offset + compass_deg, self.pitch, self.fov);
+    }
+
+    /// pitch 回到水平线
+    pub fn level_horizon(&mut self) {
+        self.animate_to(self.yaw, 0.0, self.fov);
+    }
+
+    /// 用户手动操作时中止动画，避免和拖拽/缩放互相争抢
+    pub fn stop_animation(&mut self) {
+        self.animation = None;
+    }
+
+    /// 每帧推进动画
+    pub fn update_animation(&mut self) {
+        let Some(anim) = self.animation else {
+            return;
+        };
+        let t = (anim.start.elapsed().as_secs_f32() / CAMERA_MOVE_DURATION.as_secs_f32()).min(1.0);
+        let k = ease_in_out(t);
+        let lerp = |i: usize| anim.from[i] + (anim.to[i] - anim.from[i]) * k;
+        self.yaw = lerp(0);
+        self.pitch = lerp(1);
+        // 动画途中切换了投影（保留 FOV）时，目标值可能超出新投影的范围
+        self.fov = self.clamp_fov(lerp(2));
+        if t >= 1.0 {
+            self.animation = None;
+        }
+    }
+
+    /// 按当前设置约束 pitch：限位模式下夹取，越极模式下 wrap 到 (-180°, 180°]
+    /// 平面展开模式下 pitch 表示可见窗口的垂直平移，夹取到不露出图片上下边缘
+    pub fn constrain_pitch(&self, pitch: f32) -> f32 {
+        if self.projection_mode == ProjectionMode::Equirectangular {
+            let max_pan = (90.0 - self.fov * 0.5).max(0.0);
+            pitch.clamp(-max_pan, max_pan)
+        } else if self.over_the_pole {
+            let wrapped = (pitch + 180.0).rem_euclid(360.0) - 180.0;
+            if wrapped == -180.0 {
+                180.0
+            } else {
+                wrapped
+            }
+        } else {
+            pitch.clamp(-self.pitch_limit, self.pitch_limit)
+        }
+    }
+
+    /// 视线中心允许的范围：保证画面边缘不超出有效内容
+    /// 小行星模式本来就要看整个球面，不做限制
+    fn center_bounds(&self, aspect: f32) -> Option<Coverage> {
+        let coverage = self.coverage?;
+        if self.projection_mode == ProjectionMode::Stereographic {
+            return None;
+        }
+
+        let half_v = self.fov * 0.5;
+        let half_h = if self.projection_mode == ProjectionMode::Equirectangular {
+            // 平面展开：水平与垂直每度像素相同
+            half_v * aspect
+        } else {
+            ((half_v.to_radians().tan() * aspect).atan()).to_degrees()
+        };
+
+        Some(Coverage {
+            yaw: coverage.yaw.map(|r| shrink(r, half_h)),
+            pitch: shrink(coverage.pitch, half_v),
+            ..coverage
+        })
+    }
+
+    /// 拖拽时的软限位：越界后继续向外的移动按阻尼衰减
+    pub fn resist_bounds(&self, from: (f32, f32), to: (f32, f32), aspect: f32) -> (f32, f32) {
+        let Some(Coverage { yaw: yaw_range, pitch: pitch_range, .. }) = self.center_bounds(aspect) else {
+            return to;
+        };
+
+        let resist = |from: f32, to: f32, (lo, hi): (f32, f32)| {
+            let delta = to - from;
+            if (to > hi && delta > 0.0) || (to < lo && delta < 0.0) {
+                from + delta * BOUNDS_RESISTANCE
+            } else {
+                to
+            }
+        };
+
+        let yaw = match yaw_range {
+            Some(range) => {
+                let shift = wrap_shift(from.0, range);
+                resist(from.0 - shift, to.0 - shift, range) + shift
+            }
+            None => to.0,
+        };
+        (yaw, resist(from.1, to.1, pitch_range))
+    }
+
+    /// 未拖拽时每帧把越界的视角平滑拉回有效区域（减少动态效果时一步到位）
+    pub fn settle_into_bounds(&mut self, aspect: f32, dt: f32) {
+        if self.animation.is_some() {
+            return;
+        }
+        let Some(Coverage { yaw: yaw_range, pitch: pitch_range, .. }) = self.center_bounds(aspect) else {
+            return;
+        };
+
+        let k = if self.reduce_motion { 1.0 } else { 1.0 - (-BOUNDS_SETTLE_RATE * dt).exp() };
+        let pull = |v: f32, (lo, hi): (f32, f32)| {
+            let target = v.clamp(lo, hi);
+            // 足够接近时直接吸附，避免无限逼近
+            if (target - v).abs() < 0.01 {
+                target
+            } else {
+                v + (target - v) * k
+            }
+        };
+
+        if let Some(range) = yaw_range {
+            let shift = wrap_shift(self.yaw, range);
+            self.yaw = pull(self.yaw - shift, range) + shift;
+        }
+        self.pitch = pull(self.pitch, pitch_range);
+    }
+
+    /// 越过天顶/天底后画面上下颠倒，水平拖拽方向需要随之反转
+    pub fn is_upside_down(&self) -> bool {
+        self.pitch.abs() > 90.0
+    }
 }
@@ -0,0 +1,113 @@
+// playlist.rs — 同一文件夹内的图片列表（上一张 / 下一张）
+
+use std::path::{Path, PathBuf};
+
+/// 可打开的图片扩展名（文件对话框与文件夹扫描共用；压缩包见 archive.rs）
+pub const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "bmp", "gif", "tif", "tiff", "webp"];
+
+pub fn is_image_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| IMAGE_EXTENSIONS.iter().any(|x| x.eq_ignore_ascii_case(e)))
+        .unwrap_or(false)
+}
+
+pub struct Playlist {
+    files: Vec<PathBuf>,
+    index: usize,
+}
+
+impl Playlist {
+    /// 以打开的文件所在文件夹为列表，按文件名排序；读取目录失败时只含该文件
+    pub fn from_file(path: &Path) -> Self {
+        // 只有文件名的相对路径（`panorama_viewer pano.jpg`）的 parent 是空路径，read_dir 会失败；
+        // 改写成 `./pano.jpg`，与 read_dir(".") 列出的路径形式一致
+        let path = match path.parent() {
+            Some(dir) if dir.as_os_str().is_empty() => Path::new(".").join(path),
+            _ => path.to_path_buf(),
+        };
+        let mut files: Vec<PathBuf> = path
+            .parent()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok().map(|e| e.path()))
+                    .filter(|p| p.is_file() && (is_image_file(p) || crate::archive::is_archive_file(p)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort_by_key(|p| p.file_name().map(|n| n.to_string_lossy().to_lowercase()));
+
+        let index = match files.iter().position(|p| *p == path) {
+            Some(i) => i,
+            None => {
+                files.insert(0, path);
+                0
+            }
+        };
+        Self { files, index }
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// 总是至少包含打开的那一张
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// 当前位置（从 0 开始）
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn current(&self) -> &Path {
+        &self.files[self.index]
+    }
+
+    /// 相对当前位置偏移 delta 的文件（首尾循环）；列表只有一张时返回 None
+    pub fn peek(&self, delta: isize) -> Option<&Path> {
+        if self.files.len() < 2 {
+            return None;
+        }
+        Some(&self.files[self.offset(delta)])
+    }
+
+    /// 移动到相对当前位置偏移 delta 的文件
+    pub fn step(&mut self, delta: isize) -> Option<&Path> {
+        if self.files.len() < 2 {
+            return None;
+        }
+        self.index = self.offset(delta);
+        Some(&self.files[self.index])
+    }
+
+    fn offset(&self, delta: isize) -> usize {
+        (self.index as isize + delta).rem_euclid(self.files.len() as isize) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 命令行只给文件名时也要列出同一文件夹中的其它图片
+    #[test]
+    fn bare_relative_path_lists_folder() {
+        let dir = std::env::temp_dir().join(format!("panorama_viewer_playlist_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["a.jpg", "b.png", "notes.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let cwd = std::env::current_dir().unwrap();
+        std::env::set_current_dir(&dir).unwrap();
+        let playlist = Playlist::from_file(Path::new("b.png"));
+        std::env::set_current_dir(cwd).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(playlist.len(), 2);
+        assert_eq!(playlist.current().file_name(), Some("b.png".as_ref()));
+        assert_eq!(playlist.peek(1).and_then(Path::file_name), Some("a.jpg".as_ref()));
+    }
+}
@@ -0,0 +1,583 @@
+// projection.rs — CPU 端投影数学，与 shader_equirect.wgsl 保持一一对应
+//
+// 坐标约定（与 shader 相同）：
+// - 相机空间：Right=+X, Up=+Y, Forward=-Z
+// - 屏幕坐标 ndc：x/y ∈ [-1, 1]，Y 向上
+// - 世界方向 -> 等矩形 UV：u = fract(atan2(z, x) / 2π + 0.75)，v = 0.5 - asin(y) / π
+// - pitch 为正时视线向下（与拖拽方向一致）
+//
+// 修改 shader 中任何投影分支时，请同步修改这里，并运行 `cargo test` 检查文件末尾的参考角度测试。
+
+use crate::panorama::ProjectionMode;
+use glam::{Mat3, Quat, Vec2, Vec3};
+use std::f32::consts::PI;
+
+/// 与 `Renderer::update_camera` 写入 uniform 的值完全一致的视角参数（弧度）。
+#[derive(Debug, Clone, Copy)]
+pub struct ViewParams {
+    pub aspect: f32,
+    pub fov_rad: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub mode: ProjectionMode,
+    /// 平面展开模式下中键拖动的平移量（弧度，x 向右、y 向下），其它模式忽略
+    pub pan: Vec2,
+    /// 校正全景本身倾斜的“虚拟三脚架”旋转（弧度，x 为 pitch、y 为 roll），与相机视角无关
+    pub align: Vec2,
+}
+
+impl ViewParams {
+    /// 由 UI 层的角度（度）构造，并做与 shader 相同的“安全夹取”。
+    pub fn new(yaw_deg: f32, pitch_deg: f32, fov_deg: f32, mode: ProjectionMode, aspect: f32) -> Self {
+        // 部分投影（Rectilinear/Pannini/Architectural）在 shader 内部会用到 tan(fov/2)。
+        // 当 fov == 180° 时 tan(90°) 落在奇点，会导致 Inf/NaN，最终画面全黑或闪烁。
+        // 上下限见 ProjectionMode::hard_fov_range，用户自定义的 FOV 范围也不能超出。
+        let (min_fov, max_fov) = mode.hard_fov_range();
+        let safe_fov_deg = fov_deg.clamp(min_fov, max_fov);
+
+        // 同理：pitch 若到达 ±90°，Architectural 模式里 tan(pitch) 也会爆。
+        // 其它模式直接旋转视线，任意 pitch（包括越过天顶/天底）都由 shader 自然处理。
+        let safe_pitch_deg = match mode {
+            ProjectionMode::Architectural => pitch_deg.clamp(-89.9, 89.9),
+            _ => pitch_deg,
+        };
+
+        Self {
+            aspect,
+            fov_rad: safe_fov_deg.to_radians(),
+            yaw: yaw_deg.to_radians(),
+            pitch: safe_pitch_deg.to_radians(),
+            mode,
+            pan: Vec2::ZERO,
+            align: Vec2::ZERO,
+        }
+    }
+
+    /// 附加平面展开模式的平移量（度）
+    pub fn with_pan(self, pan_deg: [f32; 2]) -> Self {
+        Self { pan: Vec2::new(pan_deg[0].to_radians(), pan_deg[1].to_radians()), ..self }
+    }
+
+    /// 附加水平校正（度，[pitch, roll]）
+    pub fn with_alignment(self, align_deg: [f32; 2]) -> Self {
+        Self { align: Vec2::new(align_deg[0].to_radians(), align_deg[1].to_radians()), ..self }
+    }
+}
+
+/// 平面展开模式：屏幕 ndc 对应的等矩形 UV（与 shader 中 mode 4 分支相同；v 可能超出 [0, 1]）
+pub fn flat_uv(ndc: Vec2, view: &ViewParams) -> Vec2 {
+    let span_v = view.fov_rad / PI;
+    let span_u = span_v * view.aspect * 0.5;
+    let u = 0.5 + (view.yaw + view.pan.x) / (2.0 * PI) + ndc.x * span_u * 0.5;
+    let v = 0.5 + (view.pitch + view.pan.y) / PI - ndc.y * span_v * 0.5;
+    Vec2::new(u, v)
+}
+
+// 旋转矩阵：与 shader 中 rotX / rotY 的列向量逐项相同
+fn rot_x(a: f32) -> Mat3 {
+    let (s, c) = a.sin_cos();
+    Mat3::from_cols(
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, c, -s),
+        Vec3::new(0.0, s, c),
+    )
+}
+
+fn rot_y(a: f32) -> Mat3 {
+    let (s, c) = a.sin_cos();
+    Mat3::from_cols(
+        Vec3::new(c, 0.0, s),
+        Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(-s, 0.0, c),
+    )
+}
+
+/// 相机空间 -> 世界空间：RotY(yaw) * RotX(pitch)
+pub fn camera_rotation(yaw: f32, pitch: f32) -> Mat3 {
+    rot_y(yaw) * rot_x(pitch)
+}
+
+/// 绕视线（-Z）旋转；只用于重新定向等矩形图，相机本身没有 roll
+fn rot_z(a: f32) -> Mat3 {
+    let (s, c) = a.sin_cos();
+    Mat3::from_cols(
+        Vec3::new(c, s, 0.0),
+        Vec3::new(-s, c, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+    )
+}
+
+/// 重新定向全景（弧度）：新图中的方向 -> 原图中的方向。
+/// 新图的正前方（u=0.5）对应原图 yaw/pitch 所指的方向；roll 为正时画面内容顺时针旋转
+pub fn panorama_rotation(yaw: f32, pitch: f32, roll: f32) -> Mat3 {
+    camera_rotation(yaw, pitch) * rot_z(roll)
+}
+
+/// 水平校正：校正后的世界方向 -> 源图中的方向，RotX(pitch) * RotZ(roll)（shader 中相同）。
+/// 拍摄时相机没有放平的全景，地平线在图中是一条起伏的曲线；把场景整体转回水平后，
+/// 相机的 yaw/pitch 都在校正后的世界中进行
+pub fn alignment_rotation(view: &ViewParams) -> Mat3 {
+    rot_x(view.align.x) * rot_z(view.align.y)
+}
+
+/// 给定 yaw/pitch（弧度）时屏幕中心所看的世界方向。
+pub fn look_direction(yaw: f32, pitch: f32) -> Vec3 {
+    camera_rotation(yaw, pitch) * Vec3::new(0.0, 0.0, -1.0)
+}
+
+/// look_direction 的逆映射：方向 -> (yaw, pitch)（弧度，yaw 在 (-π, π]）
+pub fn direction_yaw_pitch(dir: Vec3) -> (f32, f32) {
+    let dir = dir.normalize();
+    (dir.x.atan2(-dir.z), (-dir.y).clamp(-1.0, 1.0).asin())
+}
+
+/// 相机空间 -> 世界方向（源图坐标系）的旋转，与 shader 生成光线时使用的基相同：
+/// 列向量依次为屏幕右、屏幕上、视线反向（视线为 -Z 列），已计入水平校正。
+/// Architectural 模式相机保持水平（pitch 变成镜头平移），只绕 yaw 旋转；
+/// 平面展开不是旋转投影，返回画面中心（含平移）的视线方向对应的基
+pub fn view_basis(view: &ViewParams) -> Mat3 {
+    let camera = match view.mode {
+        ProjectionMode::Architectural => rot_y(view.yaw),
+        ProjectionMode::Equirectangular => camera_rotation(view.yaw + view.pan.x, view.pitch + view.pan.y),
+        _ => camera_rotation(view.yaw, view.pitch),
+    };
+    alignment_rotation(view) * camera
+}
+
+/// 供嵌入程序同步其它视图或叠加 3D 内容：当前相机姿态与投影参数
+#[derive(Debug, Clone, Copy)]
+pub struct ViewPose {
+    /// 见 view_basis
+    pub basis: Mat3,
+    /// 与 basis 相同的旋转
+    pub rotation: Quat,
+    /// 投影模式、FOV、宽高比等（与 uniform 中的值一致，已做安全夹取）
+    pub params: ViewParams,
+}
+
+impl ViewPose {
+    pub fn new(params: ViewParams) -> Self {
+        let basis = view_basis(&params);
+        Self { basis, rotation: Quat::from_mat3(&basis), params }
+    }
+
+    /// 画面中心的视线方向（平面展开模式同样适用）
+    pub fn forward(&self) -> Vec3 {
+        -self.basis.z_axis
+    }
+}
+
+/// 世界方向 -> 等矩形纹理 UV（u 已 wrap 到 [0, 1)）
+pub fn direction_to_uv(dir: Vec3) -> Vec2 {
+    let phi = dir.z.atan2(dir.x);
+    let theta = dir.y.clamp(-1.0, 1.0).asin();
+    let u = (phi / (2.0 * PI) + 0.75).rem_euclid(1.0);
+    let v = 0.5 - theta / PI;
+    Vec2::new(u, v)
+}
+
+/// 等矩形纹理 UV -> 世界方向（shader uv_to_dir，direction_to_uv 的逆映射）
+pub fn uv_to_direction(uv: Vec2) -> Vec3 {
+    let phi = (uv.x - 0.75) * 2.0 * PI;
+    let theta = (0.5 - uv.y) * PI;
+    Vec3::new(theta.cos() * phi.cos(), theta.sin(), theta.cos() * phi.sin())
+}
+
+/// 屏幕 ndc -> 世界方向（单位向量，源图坐标系，即已经过 alignment_rotation），
+/// 与 shader fs_main 的光线生成逐分支对应。
+/// 平面展开模式下返回该像素所采样的等矩形位置对应的方向（v 超出 [0, 1] 时 shader 显示黑色）。
+pub fn screen_to_direction(ndc: Vec2, view: &ViewParams) -> Vec3 {
+    alignment_rotation(view) * leveled_direction(ndc, view)
+}
+
+/// 屏幕 ndc -> 水平校正之前的世界方向
+fn leveled_direction(ndc: Vec2, view: &ViewParams) -> Vec3 {
+    let p = Vec2::new(ndc.x * view.aspect, ndc.y);
+    let r = p.length();
+    // 从光轴 (0, 0, -1) 偏转 theta、方位沿 p 方向的相机空间方向
+    let radial = |theta: f32| {
+        if r > 1e-4 {
+            let (s, c) = theta.sin_cos();
+            Vec3::new(p.x / r * s, p.y / r * s, -c)
+        } else {
+            Vec3::new(0.0, 0.0, -1.0)
+        }
+    };
+
+    let dir = match view.mode {
+        ProjectionMode::Rectilinear => {
+            let f = 1.0 / (view.fov_rad * 0.5).tan();
+            p.extend(-f).normalize()
+        }
+        ProjectionMode::Equidistant => radial(r * view.fov_rad * 0.5),
+        ProjectionMode::Stereographic => radial(2.0 * (r * (view.fov_rad * 0.25).tan()).atan()),
+        ProjectionMode::Pannini => {
+            let f = 1.0 / (view.fov_rad * 0.5).tan();
+            let theta = p.x / f;
+            Vec3::new(theta.sin(), p.y / f, -theta.cos()).normalize()
+        }
+        ProjectionMode::Equirectangular => return uv_to_direction(flat_uv(ndc, view)),
+        ProjectionMode::Architectural => {
+            let f = 1.0 / (view.fov_rad * 0.5).tan();
+            let shift = -view.pitch.tan();
+            return rot_y(view.yaw) * Vec3::new(p.x, p.y + shift * f, -f).normalize();
+        }
+    };
+
+    camera_rotation(view.yaw, view.pitch) * dir
+}
+
+/// 世界方向（源图坐标系）-> 屏幕 ndc（shader fs_main 光线生成的逆映射）。
+/// 方向在当前投影下不可见（例如位于透视相机背后）时返回 None；
+/// 返回值可能落在 [-1, 1] 之外，表示在屏幕外。
+pub fn direction_to_screen(world_dir: Vec3, view: &ViewParams) -> Option<Vec2> {
+    let world_dir = (alignment_rotation(view).transpose() * world_dir).normalize();
+
+    let p = match view.mode {
+        ProjectionMode::Rectilinear => {
+            let d = camera_rotation(view.yaw, view.pitch).transpose() * world_dir;
+            if d.z > -1e-6 {
+                return None;
+            }
+            let f = 1.0 / (view.fov_rad * 0.5).tan();
+            Vec2::new(d.x, d.y) * (f / -d.z)
+        }
+        ProjectionMode::Equidistant => {
+            let d = camera_rotation(view.yaw, view.pitch).transpose() * world_dir;
+            let theta = (-d.z).clamp(-1.0, 1.0).acos();
+            radial_point(d, theta / (view.fov_rad * 0.5))
+        }
+        ProjectionMode::Stereographic => {
+            let d = camera_rotation(view.yaw, view.pitch).transpose() * world_dir;
+            let theta = (-d.z).clamp(-1.0, 1.0).acos();
+            if theta > PI - 1e-3 {
+                return None;
+            }
+            let scale = (view.fov_rad * 0.25).tan();
+            radial_point(d, (theta * 0.5).tan() / scale)
+        }
+        ProjectionMode::Pannini => {
+            let d = camera_rotation(view.yaw, view.pitch).transpose() * world_dir;
+            let horiz = (d.x * d.x + d.z * d.z).sqrt();
+            if horiz < 1e-6 {
+                return None;
+            }
+            let f = 1.0 / (view.fov_rad * 0.5).tan();
+            let theta = d.x.atan2(-d.z);
+            Vec2::new(theta * f, d.y / horiz * f)
+        }
+        ProjectionMode::Equirectangular => {
+            let uv = direction_to_uv(world_dir);
+            let span_v = view.fov_rad / PI;
+            let span_u = span_v * view.aspect * 0.5;
+            // 取离可见窗口中心最近的那一份（水平方向是循环的）
+            let du = (uv.x - 0.5 - (view.yaw + view.pan.x) / (2.0 * PI) + 0.5).rem_euclid(1.0) - 0.5;
+            let dv = uv.y - 0.5 - (view.pitch + view.pan.y) / PI;
+            return Some(Vec2::new(du / (span_u * 0.5), -dv / (span_v * 0.5)));
+        }
+        ProjectionMode::Architectural => {
+            let d = rot_y(view.yaw).transpose() * world_dir;
+            if d.z > -1e-6 {
+                return None;
+            }
+            let f = 1.0 / (view.fov_rad * 0.5).tan();
+            let shift = -view.pitch.tan();
+            let k = f / -d.z;
+            Vec2::new(d.x * k, d.y * k - shift * f)
+        }
+    };
+
+    Some(Vec2::new(p.x / view.aspect, p.y))
+}
+
+/// 全画幅传感器尺寸（mm）
+const FULL_FRAME_MM: Vec2 = Vec2::new(36.0, 24.0);
+
+/// 把垂直 FOV 换算为全画幅等效焦距（状态栏显示；按对角线视角计算）
+pub fn equivalent_focal_mm(fov_deg: f32) -> f32 {
+    let fov_rad = fov_deg.clamp(0.01, 179.9).to_radians();
+    FULL_FRAME_MM.length() / (2.0 * (fov_rad * 0.5).tan())
+}
+
+/// equivalent_focal_mm 的逆换算：全画幅等效焦距 -> 垂直 FOV（度）
+pub fn fov_for_focal_mm(focal_mm: f32) -> f32 {
+    (2.0 * (FULL_FRAME_MM.length() / (2.0 * focal_mm.max(0.01))).atan()).to_degrees()
+}
+
+/// 全画幅镜头（焦距 focal_mm）从当前视线中心拍摄时的取景框，经当前投影映射到屏幕 ndc。
+/// 按顺时针沿四条边采样，每条边 samples_per_edge 个点；看不到的点为 None。
+/// 取景框对角线视角与 equivalent_focal_mm 的换算一致。
+pub fn lens_frame_outline(focal_mm: f32, view: &ViewParams, samples_per_edge: usize) -> Vec<Option<Vec2>> {
+    // 传感器平面放在 z = -focal_mm 处，四角即 (±18, ±12)
+    let half = FULL_FRAME_MM * 0.5;
+    let corners = [
+        Vec2::new(-half.x, half.y),
+        Vec2::new(half.x, half.y),
+        Vec2::new(half.x, -half.y),
+        Vec2::new(-half.x, -half.y),
+    ];
+    let rotation = alignment_rotation(view) * camera_rotation(view.yaw, view.pitch);
+
+    let mut points = Vec::with_capacity(samples_per_edge * 4 + 1);
+    for i in 0..4 {
+        let (a, b) = (corners[i], corners[(i + 1) % 4]);
+        for k in 0..samples_per_edge {
+            let p = a.lerp(b, k as f32 / samples_per_edge as f32);
+            points.push(direction_to_screen(rotation * p.extend(-focal_mm), view));
+        }
+    }
+    // 闭合
+    points.push(points[0]);
+    points
+}
+
+/// 等矩形纹理上的矩形区域（UV 单位）；u0 可以超出 [0, 1)，水平方向循环
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UvRect {
+    pub u0: f32,
+    pub v0: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// 当前画面用到的等矩形纹理范围（在屏幕上按网格采样，近似值）
+pub fn visible_uv_rect(view: &ViewParams) -> UvRect {
+    const N: usize = 8;
+    let center_u = direction_to_uv(screen_to_direction(Vec2::ZERO, view)).x;
+    let (mut du_min, mut du_max) = (0.0f32, 0.0f32);
+    let (mut v_min, mut v_max) = (1.0f32, 0.0f32);
+    for i in 0..=N {
+        for j in 0..=N {
+            let ndc = Vec2::new(i as f32 / N as f32 * 2.0 - 1.0, j as f32 / N as f32 * 2.0 - 1.0);
+            let uv = direction_to_uv(screen_to_direction(ndc, view));
+            // 相对中心的水平偏移 wrap 到 [-0.5, 0.5)
+            let du = (uv.x - center_u + 0.5).rem_euclid(1.0) - 0.5;
+            du_min = du_min.min(du);
+            du_max = du_max.max(du);
+            v_min = v_min.min(uv.y);
+            v_max = v_max.max(uv.y);
+        }
+    }
+    UvRect { u0: center_u + du_min, v0: v_min, width: du_max - du_min, height: (v_max - v_min).max(0.0) }
+}
+
+/// 屏幕中心处每弧度对应多少屏幕像素（ndc 的 y=1 对应 screen_height / 2 像素）
+pub fn center_pixels_per_radian(view: &ViewParams, screen_height: f32) -> f32 {
+    let half = view.fov_rad * 0.5;
+    // ndc 半径 r 关于视线夹角 θ 在 θ=0 处的导数
+    let dr_dtheta = match view.mode {
+        // r = tan(θ) / tan(fov/2)；Pannini(圆柱) 水平 x = θ / tan(fov/2)，中心处相同
+        ProjectionMode::Rectilinear | ProjectionMode::Pannini | ProjectionMode::Architectural => {
+            1.0 / half.tan()
+        }
+        // r = θ / (fov/2)
+        ProjectionMode::Equidistant => 1.0 / half,
+        // r = tan(θ/2) / tan(fov/4)
+        ProjectionMode::Stereographic => 0.5 / (view.fov_rad * 0.25).tan(),
+        // 平面展开：垂直方向整屏高度对应 fov
+        ProjectionMode::Equirectangular => 2.0 / view.fov_rad,
+    };
+    dr_dtheta * screen_height * 0.5
+}
+
+fn radial_point(d: Vec3, r: f32) -> Vec2 {
+    let xy = Vec2::new(d.x, d.y);
+    let len = xy.length();
+    if len > 1e-6 {
+        xy / len * r
+    } else {
+        Vec2::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPS: f32 = 1e-4;
+
+    fn view(mode: ProjectionMode, yaw_deg: f32, pitch_deg: f32, fov_deg: f32) -> ViewParams {
+        ViewParams::new(yaw_deg, pitch_deg, fov_deg, mode, 16.0 / 9.0)
+    }
+
+    fn angle_between(a: Vec3, b: Vec3) -> f32 {
+        a.normalize().dot(b.normalize()).clamp(-1.0, 1.0).acos()
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < EPS, "{a} != {b}");
+    }
+
+    /// 除平面展开外，屏幕中心都看向 yaw/pitch 所指的方向
+    #[test]
+    fn center_maps_to_look_direction() {
+        for mode in ProjectionMode::ALL {
+            if mode == ProjectionMode::Equirectangular || mode == ProjectionMode::Architectural {
+                continue;
+            }
+            let v = view(mode, 35.0, -20.0, 90.0);
+            let dir = screen_to_direction(Vec2::ZERO, &v);
+            assert!(angle_between(dir, look_direction(v.yaw, v.pitch)) < EPS, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn rectilinear_edges_at_half_fov() {
+        let v = view(ProjectionMode::Rectilinear, 0.0, 0.0, 70.0);
+        let look = look_direction(0.0, 0.0);
+        for ndc in [Vec2::new(0.0, 1.0), Vec2::new(0.0, -1.0)] {
+            assert_close(angle_between(screen_to_direction(ndc, &v), look), v.fov_rad * 0.5);
+        }
+        // 水平边缘：tan(θ) = aspect · tan(fov/2)
+        let expected = (v.aspect * (v.fov_rad * 0.5).tan()).atan();
+        for ndc in [Vec2::new(1.0, 0.0), Vec2::new(-1.0, 0.0)] {
+            assert_close(angle_between(screen_to_direction(ndc, &v), look), expected);
+        }
+        // 直线保持直线：同一行的点都在过相机的同一平面内
+        let row: Vec<Vec3> = [-0.8, 0.1, 0.9].iter().map(|&x| screen_to_direction(Vec2::new(x, 0.5), &v)).collect();
+        assert!(row[0].cross(row[1]).normalize().dot(row[2]).abs() < EPS);
+    }
+
+    #[test]
+    fn equidistant_angle_is_linear_in_radius() {
+        let v = view(ProjectionMode::Equidistant, 0.0, 0.0, 120.0);
+        let look = look_direction(0.0, 0.0);
+        for r in [0.0, 0.25, 0.5, 1.0] {
+            let ndc = Vec2::new(0.0, r);
+            assert_close(angle_between(screen_to_direction(ndc, &v), look), r * v.fov_rad * 0.5);
+        }
+    }
+
+    #[test]
+    fn stereographic_edge_at_half_fov() {
+        let v = view(ProjectionMode::Stereographic, 0.0, 90.0, 140.0);
+        let look = look_direction(v.yaw, v.pitch);
+        assert_close(angle_between(screen_to_direction(Vec2::new(0.0, 1.0), &v), look), v.fov_rad * 0.5);
+        // r = tan(θ/2) / tan(fov/4)：半径 0.5 处的角度
+        let expected = 2.0 * (0.5 * (v.fov_rad * 0.25).tan()).atan();
+        assert_close(angle_between(screen_to_direction(Vec2::new(0.0, 0.5), &v), look), expected);
+    }
+
+    #[test]
+    fn pannini_horizontal_angle_is_linear() {
+        let v = view(ProjectionMode::Pannini, 0.0, 0.0, 100.0);
+        let f = 1.0 / (v.fov_rad * 0.5).tan();
+        for x in [-1.0, -0.3, 0.6, 1.0] {
+            let dir = screen_to_direction(Vec2::new(x, 0.0), &v);
+            assert_close(dir.x.atan2(-dir.z), x * v.aspect / f);
+        }
+        let top = screen_to_direction(Vec2::new(0.0, 1.0), &v);
+        assert_close(angle_between(top, look_direction(0.0, 0.0)), v.fov_rad * 0.5);
+    }
+
+    #[test]
+    fn equirectangular_window_spans_fov() {
+        let v = view(ProjectionMode::Equirectangular, 40.0, 10.0, 90.0);
+        let center = direction_to_uv(screen_to_direction(Vec2::ZERO, &v));
+        assert_close(center.x, 0.5 + 40.0 / 360.0);
+        assert_close(center.y, 0.5 + 10.0 / 180.0);
+        // 屏幕上下边缘相差 fov（垂直方向每度像素恒定）
+        let top = direction_to_uv(screen_to_direction(Vec2::new(0.0, 1.0), &v));
+        let bottom = direction_to_uv(screen_to_direction(Vec2::new(0.0, -1.0), &v));
+        assert_close((bottom.y - top.y) * 180.0, 90.0);
+    }
+
+    #[test]
+    fn architectural_keeps_verticals_vertical() {
+        let v = view(ProjectionMode::Architectural, 20.0, -30.0, 70.0);
+        // 同一屏幕列上的点方位角相同，即竖直线在画面中仍是竖直的
+        let azimuth = |y: f32| {
+            let d = screen_to_direction(Vec2::new(0.4, y), &v);
+            d.x.atan2(-d.z)
+        };
+        assert_close(azimuth(-0.9), azimuth(0.0));
+        assert_close(azimuth(0.9), azimuth(0.0));
+        // pitch 为 0 时与标准透视相同
+        let flat = view(ProjectionMode::Architectural, 20.0, 0.0, 70.0);
+        let rect = view(ProjectionMode::Rectilinear, 20.0, 0.0, 70.0);
+        let ndc = Vec2::new(0.3, -0.7);
+        assert!(angle_between(screen_to_direction(ndc, &flat), screen_to_direction(ndc, &rect)) < EPS);
+    }
+
+    /// direction_to_screen 是 screen_to_direction 的逆映射（叠加层与画面对齐的前提）
+    #[test]
+    fn screen_round_trip() {
+        for mode in ProjectionMode::ALL {
+            let v = view(mode, -60.0, 25.0, 80.0);
+            for ndc in [Vec2::new(0.0, 0.0), Vec2::new(0.5, -0.25), Vec2::new(-0.9, 0.8)] {
+                let back = direction_to_screen(screen_to_direction(ndc, &v), &v).expect("visible");
+                assert!((back - ndc).length() < 1e-3, "{mode:?}: {ndc} -> {back}");
+            }
+        }
+    }
+
+    #[test]
+    fn alignment_tilts_the_source() {
+        // 源图本身向前倾了 10°：校正后正前方的水平视线采样源图中 pitch 10° 的位置
+        let level = view(ProjectionMode::Rectilinear, 0.0, 0.0, 90.0).with_alignment([10.0, 0.0]);
+        let tilted = look_direction(0.0, 10f32.to_radians());
+        assert!(angle_between(screen_to_direction(Vec2::ZERO, &level), tilted) < EPS);
+
+        for mode in ProjectionMode::ALL {
+            let v = view(mode, -60.0, 25.0, 80.0).with_alignment([-12.0, 7.0]);
+            for ndc in [Vec2::new(0.0, 0.0), Vec2::new(0.5, -0.25), Vec2::new(-0.9, 0.8)] {
+                let back = direction_to_screen(screen_to_direction(ndc, &v), &v).expect("visible");
+                assert!((back - ndc).length() < 1e-3, "{mode:?}: {ndc} -> {back}");
+            }
+        }
+    }
+
+    #[test]
+    fn view_basis_is_orthonormal() {
+        for mode in ProjectionMode::ALL {
+            let cases = [(0.0, 0.0, [0.0, 0.0]), (35.0, -20.0, [5.0, -15.0]), (-150.0, 80.0, [-30.0, 40.0])];
+            for (yaw, pitch, align) in cases {
+                let pose = ViewPose::new(view(mode, yaw, pitch, 90.0).with_alignment(align));
+                let b = pose.basis;
+                for (axis, other) in [(b.x_axis, b.y_axis), (b.y_axis, b.z_axis), (b.z_axis, b.x_axis)] {
+                    assert_close(axis.length(), 1.0);
+                    assert_close(axis.dot(other), 0.0);
+                }
+                // 右手系，且四元数与矩阵表示同一旋转
+                assert_close(b.determinant(), 1.0);
+                assert!((pose.rotation * Vec3::NEG_Z - pose.forward()).length() < EPS, "{mode:?}");
+            }
+        }
+    }
+
+    /// 视线与 shader 在画面中心生成的光线一致
+    #[test]
+    fn view_pose_forward_matches_center_ray() {
+        for mode in ProjectionMode::ALL {
+            if mode == ProjectionMode::Architectural {
+                continue;
+            }
+            let v = view(mode, -60.0, 25.0, 80.0).with_alignment([-12.0, 7.0]).with_pan([10.0, -5.0]);
+            let center = screen_to_direction(Vec2::ZERO, &v);
+            assert!(angle_between(ViewPose::new(v).forward(), center) < EPS, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn focal_fov_round_trip() {
+        for focal in [14.0, 24.0, 50.0, 135.0] {
+            assert!((equivalent_focal_mm(fov_for_focal_mm(focal)) - focal).abs() < 1e-2, "{focal}");
+        }
+    }
+
+    #[test]
+    fn uv_round_trip() {
+        for uv in [Vec2::new(0.5, 0.5), Vec2::new(0.1, 0.3), Vec2::new(0.9, 0.8)] {
+            assert!((direction_to_uv(uv_to_direction(uv)) - uv).length() < EPS);
+        }
+    }
+
+    #[test]
+    fn yaw_pitch_round_trip() {
+        for (yaw, pitch) in [(0.0f32, 0.0f32), (-120.0, 35.0), (170.0, -80.0)] {
+            let (y, p) = direction_yaw_pitch(look_direction(yaw.to_radians(), pitch.to_radians()));
+            assert_close(y.to_degrees(), yaw);
+            assert_close(p.to_degrees(), pitch);
+        }
+    }
+}
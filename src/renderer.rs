@@ -1,19 +1,59 @@
 // renderer.rs — 核心渲染器 (Ray Casting / Fullscreen Quad)
 
-use crate::panorama::ProjectionMode;
-use image::{GenericImage, Rgba, RgbaImage};
+use crate::mesh_renderer::{MeshRenderer, RenderPath};
+use crate::metadata::GPanoCrop;
+use crate::panorama::{Coverage, ProjectionMode};
+use crate::projection::ViewParams;
+use crate::shader_dev::ShaderWatcher;
+use image::RgbaImage;
+use std::time::{Duration, Instant};
 use wgpu::util::DeviceExt;
 use winit::window::Window;
 
+/// `embed-fonts` 特性：编译进程序的 Noto 字体，按顺序回退（拉丁/西里尔 → CJK 子集 → 阿拉伯语子集）。
+/// 文件需在构建前放到 assets/fonts/（见该目录下的 README.md）
+#[cfg(feature = "embed-fonts")]
+const EMBEDDED_FONTS: &[(&str, &[u8])] = &[
+    ("noto_sans", include_bytes!("../assets/fonts/NotoSans-Regular.ttf")),
+    ("noto_sans_cjk", include_bytes!("../assets/fonts/NotoSansCJK-Subset.otf")),
+    ("noto_sans_arabic", include_bytes!("../assets/fonts/NotoSansArabic-Subset.ttf")),
+];
+
 fn setup_egui_ui_fonts(ctx: &egui::Context) {
+    // 启用 embed-fonts 时直接使用内嵌字体，不搜索文件系统；默认构建在运行时搜索，避免程序体积膨胀
+    #[cfg(feature = "embed-fonts")]
+    let ui_fonts: Vec<(&str, egui::FontData)> = EMBEDDED_FONTS
+        .iter()
+        .map(|&(name, bytes)| (name, egui::FontData::from_static(bytes)))
+        .collect();
+    #[cfg(not(feature = "embed-fonts"))]
+    let Some(ui_fonts) = search_ui_font().map(|bytes| vec![("ui", egui::FontData::from_owned(bytes))]) else {
+        return;
+    };
+
+    // 排在 egui 默认字体之前，多个字体按顺序回退
+    let mut fonts = egui::FontDefinitions::default();
+    for (i, (name, data)) in ui_fonts.into_iter().enumerate() {
+        fonts.font_data.insert(name.to_owned(), data);
+        for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+            if let Some(list) = fonts.families.get_mut(&family) {
+                list.insert(i, name.to_owned());
+            }
+        }
+    }
+    ctx.set_fonts(fonts);
+}
+
+#[cfg(not(feature = "embed-fonts"))]
+fn search_ui_font() -> Option<Vec<u8>> {
     // UI 字体加载策略（多语言）：
     // - 运行时动态搜索：系统字体目录 +（可选）exe 同目录/工作目录的 ./assets
     // - 尽量覆盖：中文/繁中/日文/韩文/西里尔/阿拉伯语等
     //
     // 说明：ab_glyph 对 .ttc 支持不稳定，因此优先 .ttf/.otf；.ttc 仍会尝试，失败会自动跳过。
 
-    fn try_parse_owned(bytes: &Vec<u8>) -> bool {
-        ab_glyph::FontArc::try_from_vec(bytes.clone()).is_ok()
+    fn try_parse_owned(bytes: &[u8]) -> bool {
+        ab_glyph::FontArc::try_from_vec(bytes.to_vec()).is_ok()
     }
 
     fn try_load_font_from_path(path: &std::path::Path) -> Option<Vec<u8>> {
@@ -176,30 +216,41 @@ fn setup_egui_ui_fonts(ctx: &egui::Context) {
     }
 
     let Some((font_path, font_bytes)) = chosen else {
-        eprintln!("{}", crate::i18n::tr("font.not_found"));
-        return;
+        log::warn!("{}", crate::i18n::tr("font.not_found"));
+        return None;
     };
 
-    eprintln!(
+    log::info!(
         "{}",
         crate::i18n::tr_with(
             "font.using",
             &[("path", font_path.display().to_string())]
         )
     );
+    Some(font_bytes)
+}
 
-    let mut fonts = egui::FontDefinitions::default();
-    fonts.font_data.insert(
-        "ui".to_owned(),
-        egui::FontData::from_owned(font_bytes),
-    );
-    if let Some(family) = fonts.families.get_mut(&egui::FontFamily::Proportional) {
-        family.insert(0, "ui".to_owned());
-    }
-    if let Some(family) = fonts.families.get_mut(&egui::FontFamily::Monospace) {
-        family.insert(0, "ui".to_owned());
+/// 诊断窗口用的帧统计，由 render_with_ui 记录
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    /// 上一帧的 CPU 耗时：从获取交换链图像到 present（含界面构建与提交）
+    pub cpu_time: Duration,
+    /// 其中 queue.submit + present 的耗时
+    pub submit_time: Duration,
+    /// 最近一次获取交换链图像失败的错误及时间
+    pub last_surface_error: Option<(wgpu::SurfaceError, Instant)>,
+}
+
+/// CameraUniform.mode 的取值，与 shader 中的分支对应
+fn mode_index(mode: ProjectionMode) -> u32 {
+    match mode {
+        ProjectionMode::Rectilinear => 0,
+        ProjectionMode::Equidistant => 1,
+        ProjectionMode::Stereographic => 2,
+        ProjectionMode::Pannini => 3,
+        ProjectionMode::Equirectangular => 4,
+        ProjectionMode::Architectural => 5,
     }
-    ctx.set_fonts(fonts);
 }
 
 #[repr(C)]
@@ -210,9 +261,196 @@ struct CameraUniform {
     yaw: f32,
     pitch: f32,
     mode: u32, // 0=Rect, 1=Equidist, 2=Stereo, 3=Pannini, 4=Equirect, 5=Arch
-    pad1: f32,
-    pad2: f32,
-    pad3: f32,
+    source: u32, // 0=等矩形纹理, 1=立方体贴图
+    seam_blend: f32, // 拼接缝羽化宽度（u 单位，0=关闭）
+    linear_source: u32, // 1=源图数据已是线性值，撤销纹理采样时的 sRGB 解码
+
+    detail: [f32; 4], // 细节纹理在全图中的范围 (u0, v0, 宽, 高)，全 0 表示没有
+    pan: [f32; 2], // 平面展开模式的平移量（弧度，x 向右、y 向下）
+    encode_output: u32, // 1=输出表面不是 sRGB 格式，由 shader 自行编码
+    vignette_strength: f32, // 暗角强度（画面四角的压暗比例，0=关闭）
+
+    exposure: f32, // 曝光倍率（2^EV），1=不调整
+    align_pitch: f32, // 水平校正（弧度），在投影之前旋转采样方向
+    align_roll: f32,
+    load_fade: f32, // 新图片淡入的进度（0..1，乘在最终颜色上），1=完全显示
+
+    tex_rows: [f32; 2], // 纹理在等矩形画布中的行范围 (v0, 高度)；条幅全景的纹理只含有效行，其余为 (0, 1)
+    _pad2: [f32; 2],
+}
+
+/// 细节纹理在可见范围外每侧多裁出的比例，小幅平移时不必重新裁剪
+const DETAIL_MARGIN: f32 = 0.25;
+
+/// 分帧上传时每帧最多写入的字节数（8K×4K 的 RGBA 约分 8 帧写完）
+const UPLOAD_BUDGET_BYTES: usize = 16 << 20;
+
+/// 新图片上传完成后从黑色淡入的时长
+const LOAD_FADE_DURATION: Duration = Duration::from_millis(200);
+
+/// 已上传纹理的 CPU 端副本；超过 GPU 限制的等矩形图保留的是全分辨率原图
+enum SourceImage {
+    Equirect(RgbaImage),
+    Cubemap(Box<[RgbaImage; 6]>),
+}
+
+/// 进行中的分帧纹理上传
+struct PendingUpload {
+    texture: wgpu::Texture,
+    image: RgbaImage,
+    next_row: u32,
+    coverage: Option<Coverage>,
+    full_resolution: Option<RgbaImage>,
+}
+
+/// 把 img 的 [start, end) 行写入纹理的相同位置
+fn write_rows(queue: &wgpu::Queue, texture: &wgpu::Texture, img: &RgbaImage, start: u32, end: u32) {
+    let width = img.width();
+    let row_bytes = 4 * width as usize;
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d { x: 0, y: start, z: 0 },
+            aspect: wgpu::TextureAspect::All,
+        },
+        &img.as_raw()[start as usize * row_bytes..end as usize * row_bytes],
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * width),
+            rows_per_image: Some(end - start),
+        },
+        wgpu::Extent3d { width, height: end - start, depth_or_array_layers: 1 },
+    );
+}
+
+fn create_cube_texture(device: &wgpu::Device, size: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        size: wgpu::Extent3d { width: size, height: size, depth_or_array_layers: 6 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        label: Some("cube_texture"),
+        view_formats: &[],
+    })
+}
+
+fn create_detail_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        label: Some("detail_texture"),
+        view_formats: &[],
+    })
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    camera_buffer: &wgpu::Buffer,
+    texture: &wgpu::Texture,
+    cube_texture: &wgpu::Texture,
+    detail_texture: &wgpu::Texture,
+    sampler: &wgpu::Sampler,
+) -> wgpu::BindGroup {
+    let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let cube_view = cube_texture.create_view(&wgpu::TextureViewDescriptor {
+        dimension: Some(wgpu::TextureViewDimension::Cube),
+        ..Default::default()
+    });
+    let detail_view = detail_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: camera_buffer.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&texture_view) },
+            wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(sampler) },
+            wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(&cube_view) },
+            wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(&detail_view) },
+        ],
+        label: Some("diffuse_bind_group"),
+    })
+}
+
+/// 着色器编译失败时使用的占位着色器：全屏深灰色，界面仍可正常使用并显示错误
+const FALLBACK_SHADER: &str = "
+@vertex
+fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    var positions = array<vec2<f32>, 3>(vec2<f32>(-1.0, -1.0), vec2<f32>(3.0, -1.0), vec2<f32>(-1.0, 3.0));
+    return vec4<f32>(positions[i], 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.1, 0.1, 0.1, 1.0);
+}
+";
+
+/// 编译 WGSL 并创建渲染管线；用 error scope 捕获解析/校验错误，避免 wgpu 默认的 panic
+fn build_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    source: &str,
+    format: wgpu::TextureFormat,
+) -> Result<wgpu::RenderPipeline, String> {
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("shader_equirect"),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+    let pipeline = create_render_pipeline(device, layout, &shader, format);
+    match pollster::block_on(device.pop_error_scope()) {
+        Some(err) => Err(err.to_string()),
+        None => Ok(pipeline),
+    }
+}
+
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Render Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[], // 无顶点缓冲，Shader 自生成
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None, // 不要剔除，因为我们要画一个覆盖全屏的三角形
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None, // 不需要深度缓冲，全屏绘制
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
 }
 
 pub struct Renderer {
@@ -222,16 +460,48 @@ pub struct Renderer {
     config: wgpu::SurfaceConfiguration,
     pub size: winit::dpi::PhysicalSize<u32>,
     render_pipeline: wgpu::RenderPipeline,
+    render_pipeline_layout: wgpu::PipelineLayout,
+    // 备用的网格球体渲染，首次切换到 RenderPath::Mesh 时才创建
+    render_path: RenderPath,
+    mesh_renderer: Option<MeshRenderer>,
+
+    // 着色器热重载（仅开发调试时启用）
+    shader_watcher: Option<ShaderWatcher>,
+    shader_error: Option<String>,
+    // 已上传纹理每度对应的纹素数（缩放到 GPU 限制之后的实际值）；未加载图片时为 None
+    texels_per_degree: Option<f32>,
+    // 用户设置的纹理边长上限（节省显存）；None 为只受 GPU 限制
+    texture_limit: Option<u32>,
+    // 新图片淡入：是否开启，以及进行中的淡入的开始时间
+    load_fade_enabled: bool,
+    load_fade_start: Option<Instant>,
+    // 局部全景的有效内容范围；完整全景或立方体贴图为 None
+    coverage: Option<Coverage>,
+    uncaptured_error: std::sync::Arc<std::sync::Mutex<Option<String>>>,
     
     // 纹理资源
     texture_bind_group_layout: wgpu::BindGroupLayout,
     diffuse_bind_group: wgpu::BindGroup,
     texture: wgpu::Texture,
+    cube_texture: wgpu::Texture,
+    // 超大等矩形图的细节纹理及其在全分辨率原图中的范围 (x, y, w, h)；x + w 可越过右边缘（循环）
+    detail_texture: wgpu::Texture,
+    detail_rect: Option<[u32; 4]>,
+    // 分帧上传中的下一张等矩形纹理
+    upload: Option<PendingUpload>,
+    // 当前纹理的 CPU 端副本（与 GPU 上的像素相同），供取色器读取原始像素
+    source: Option<SourceImage>,
     sampler: wgpu::Sampler,
     
     // Uniform 资源
     camera_uniform: CameraUniform,
     camera_buffer: wgpu::Buffer,
+    // A/B 对比：右半边的投影（None 为关闭），以及右半边单独的 uniform 与 bind group
+    split_mode: Option<ProjectionMode>,
+    split_camera_buffer: wgpu::Buffer,
+    split_bind_group: wgpu::BindGroup,
+
+    frame_stats: FrameStats,
 
     // UI
     pub egui_ctx: egui::Context,
@@ -241,7 +511,9 @@ pub struct Renderer {
 
 impl Renderer {
     pub async fn new(window: std::sync::Arc<Window>) -> Self {
+        // 以最小化状态启动时 inner_size 可能是 0×0，表面至少配置为 1×1（宽高比也不会是 NaN）
         let size = window.inner_size();
+        let size = winit::dpi::PhysicalSize::new(size.width.max(1), size.height.max(1));
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
             backends: wgpu::Backends::all(),
             ..Default::default()
@@ -272,7 +544,14 @@ impl Renderer {
             .copied()
             .find(|f| f.is_srgb())
             .unwrap_or(surface_caps.formats[0]);
-            
+        // 没有 sRGB 格式的表面：输出改由 shader 编码（CameraUniform::encode_output），egui-wgpu 也会按格式自行处理
+        if !surface_format.is_srgb() {
+            log::warn!(
+                "{}",
+                crate::i18n::tr_with("gpu.surface_not_srgb", &[("format", format!("{:?}", surface_format))])
+            );
+        }
+
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
@@ -284,8 +563,10 @@ impl Renderer {
         };
         surface.configure(&device, &config);
 
-        // --- 1. Texture Setup (Default Checkerboard) ---
-        let texture_size = wgpu::Extent3d { width: 2, height: 2, depth_or_array_layers: 1 };
+        // --- 1. Texture Setup (Placeholder) ---
+        // 还没有图片时显示的中性灰“空场景”：上半球略亮、下半球略暗，地平线处平滑过渡；
+        // 操作提示由界面层叠加（见 app.rs draw_placeholder）
+        let texture_size = wgpu::Extent3d { width: 1, height: 2, depth_or_array_layers: 1 };
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             size: texture_size,
             mip_level_count: 1,
@@ -297,15 +578,24 @@ impl Renderer {
             view_formats: &[],
         });
         
-        // 初始写入一些数据防止全黑
         queue.write_texture(
             wgpu::ImageCopyTexture { texture: &texture, mip_level: 0, origin: wgpu::Origin3d::ZERO, aspect: wgpu::TextureAspect::All },
-            &[255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 0, 255],
-            wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(8), rows_per_image: Some(2) },
+            &[72, 76, 84, 255, 36, 38, 42, 255],
+            wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(4), rows_per_image: Some(2) },
             texture_size,
         );
 
-        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        // 立方体贴图占位（1x1 黑色 x 6 层），加载六面体全景时替换
+        let cube_texture = create_cube_texture(&device, 1);
+        queue.write_texture(
+            wgpu::ImageCopyTexture { texture: &cube_texture, mip_level: 0, origin: wgpu::Origin3d::ZERO, aspect: wgpu::TextureAspect::All },
+            &[0, 0, 0, 255].repeat(6),
+            wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(4), rows_per_image: Some(1) },
+            wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 6 },
+        );
+        // 细节纹理占位，camera.detail 为 0 时 shader 不会使用
+        let detail_texture = create_detail_texture(&device, 1, 1);
+
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::Repeat, // 全景图通常需要水平循环
             address_mode_v: wgpu::AddressMode::ClampToEdge,
@@ -323,7 +613,19 @@ impl Renderer {
             yaw: 0.0,
             pitch: 0.0,
             mode: 0,
-            pad1: 0.0, pad2: 0.0, pad3: 0.0,
+            source: 0,
+            seam_blend: 0.0,
+            linear_source: 0,
+            detail: [0.0; 4],
+            pan: [0.0; 2],
+            encode_output: u32::from(!config.format.is_srgb()),
+            vignette_strength: 0.0,
+            exposure: 1.0,
+            align_pitch: 0.0,
+            align_roll: 0.0,
+            load_fade: 1.0,
+            tex_rows: [0.0, 1.0],
+            _pad2: [0.0; 2],
         };
 
         let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -331,6 +633,11 @@ impl Renderer {
             contents: bytemuck::cast_slice(&[camera_uniform]),
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
+        let split_camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Split Camera Buffer"),
+            contents: bytemuck::cast_slice(&[camera_uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
 
         let texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             entries: &[
@@ -360,62 +667,82 @@ impl Renderer {
                     ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry { // Cube Texture
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry { // Detail Texture
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
             ],
             label: Some("texture_bind_group_layout"),
         });
 
-        let diffuse_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry { binding: 0, resource: camera_buffer.as_entire_binding() },
-                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&texture_view) },
-                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&sampler) },
-            ],
-            label: Some("diffuse_bind_group"),
-        });
+        let diffuse_bind_group = create_bind_group(
+            &device,
+            &texture_bind_group_layout,
+            &camera_buffer,
+            &texture,
+            &cube_texture,
+            &detail_texture,
+            &sampler,
+        );
+        let split_bind_group = create_bind_group(
+            &device,
+            &texture_bind_group_layout,
+            &split_camera_buffer,
+            &texture,
+            &cube_texture,
+            &detail_texture,
+            &sampler,
+        );
 
         // --- 3. Pipeline Setup ---
-        let shader = device.create_shader_module(wgpu::include_wgsl!("shader_equirect.wgsl"));
+        // 其余未被 error scope 捕获的 wgpu 错误也不再 panic，而是记录下来显示在界面上
+        let uncaptured_error = std::sync::Arc::new(std::sync::Mutex::new(None));
+        {
+            let slot = uncaptured_error.clone();
+            device.on_uncaptured_error(Box::new(move |err| {
+                log::error!("wgpu: {}", err);
+                if let Ok(mut slot) = slot.lock() {
+                    *slot = Some(err.to_string());
+                }
+            }));
+        }
         let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
             bind_group_layouts: &[&texture_bind_group_layout],
             push_constant_ranges: &[],
         });
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[], // 无顶点缓冲，Shader 自生成
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None, // 不要剔除，因为我们要画一个覆盖全屏的三角形
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None, // 不需要深度缓冲，全屏绘制
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-        });
+        let (render_pipeline, shader_error) = match build_pipeline(
+            &device,
+            &render_pipeline_layout,
+            include_str!("shader_equirect.wgsl"),
+            config.format,
+        ) {
+            Ok(pipeline) => (pipeline, None),
+            Err(err) => {
+                log::error!("{}", err);
+                let fallback =
+                    build_pipeline(&device, &render_pipeline_layout, FALLBACK_SHADER, config.format)
+                        .expect("fallback shader is valid");
+                (fallback, Some(err))
+            }
+        };
 
         // --- 4. Egui Setup ---
         let egui_ctx = egui::Context::default();
@@ -430,10 +757,19 @@ impl Renderer {
 
         Self {
             surface, device, queue, config, size,
-            render_pipeline,
+            render_pipeline, render_pipeline_layout,
+            render_path: RenderPath::RayCast, mesh_renderer: None,
+            shader_watcher: None, shader_error, uncaptured_error,
+            texels_per_degree: None,
+            texture_limit: None,
+            load_fade_enabled: false,
+            load_fade_start: None,
+            coverage: None,
             texture_bind_group_layout, diffuse_bind_group,
-            texture, sampler,
+            texture, cube_texture, detail_texture, detail_rect: None, upload: None, source: None, sampler,
             camera_uniform, camera_buffer,
+            split_mode: None, split_camera_buffer, split_bind_group,
+            frame_stats: FrameStats::default(),
             egui_ctx, egui_state, egui_renderer,
         }
     }
@@ -448,97 +784,345 @@ impl Renderer {
         }
     }
 
-    pub fn update_camera(&mut self, yaw: f32, pitch: f32, fov: f32, mode: ProjectionMode) {
-        // 重要：部分投影（Rectilinear/Pannini/Architectural）在 shader 内部会用到 tan(fov/2)。
-        // 当 fov == 180° 时 tan(90°) 落在奇点，会导致 Inf/NaN，最终画面全黑或闪烁。
-        // 这里做一次“安全夹取”，并保持 UI 层仍可显示 180°。
-        let safe_fov_deg = match mode {
-            ProjectionMode::Rectilinear | ProjectionMode::Pannini | ProjectionMode::Architectural => {
-                fov.clamp(1.0, 179.9)
+    pub fn render_path(&self) -> RenderPath {
+        self.render_path
+    }
+
+    /// 切换渲染方式；网格管线创建失败时返回错误并保持光线投射
+    pub fn set_render_path(&mut self, path: RenderPath) -> Result<(), String> {
+        if path == RenderPath::Mesh && self.mesh_renderer.is_none() {
+            let mesh = MeshRenderer::new(&self.device, &self.texture_bind_group_layout, self.config.format)?;
+            self.mesh_renderer = Some(mesh);
+        }
+        self.render_path = path;
+        Ok(())
+    }
+
+    /// 开启着色器热重载：之后每帧调用 poll_shader_reload 检查文件变化
+    pub fn enable_shader_hot_reload(&mut self, path: std::path::PathBuf) {
+        self.shader_watcher = Some(ShaderWatcher::new(path));
+    }
+
+    /// 最近一次着色器编译/校验失败或其它 GPU 错误的信息；热重载成功后清空
+    pub fn shader_error(&self) -> Option<&str> {
+        self.shader_error.as_deref()
+    }
+
+    /// 着色器文件有修改时重新编译并替换渲染管线；出错时保留旧管线，错误记录到 shader_error
+    pub fn poll_shader_reload(&mut self) {
+        let Some(watcher) = self.shader_watcher.as_mut() else {
+            return;
+        };
+        let Some(source) = watcher.poll() else {
+            return;
+        };
+        let path = watcher.path().display().to_string();
+
+        let source = match source {
+            Ok(source) => source,
+            Err(e) => {
+                self.shader_error = Some(e);
+                return;
             }
-            _ => fov.clamp(1.0, 180.0),
         };
 
-        // 同理：pitch 若到达 ±90°，Architectural 模式里 tan(pitch) 也会爆。
-        let safe_pitch_deg = pitch.clamp(-89.9, 89.9);
+        match build_pipeline(
+            &self.device,
+            &self.render_pipeline_layout,
+            &source,
+            self.config.format,
+        ) {
+            Ok(pipeline) => {
+                self.render_pipeline = pipeline;
+                self.shader_error = None;
+                log::info!("{}", crate::i18n::tr_with("log.shader_reloaded", &[("path", path)]));
+            }
+            Err(err) => self.shader_error = Some(err),
+        }
+    }
 
-        self.camera_uniform.yaw = yaw.to_radians();
-        self.camera_uniform.pitch = safe_pitch_deg.to_radians();
-        self.camera_uniform.fov_rad = safe_fov_deg.to_radians();
+    /// 把 on_uncaptured_error 记录的错误转到 shader_error（每帧调用）
+    pub fn poll_errors(&mut self) {
+        if let Some(err) = self.uncaptured_error.lock().ok().and_then(|mut e| e.take()) {
+            self.shader_error = Some(err);
+        }
+    }
+
+    /// 实际使用的最大 2D 纹理边长（GPU 上限与 set_texture_limit 取较小者）；后台线程据此提前缩放图片
+    pub fn max_texture_dimension(&self) -> u32 {
+        let gpu = self.gpu_max_texture_dimension();
+        self.texture_limit.map_or(gpu, |limit| limit.clamp(1, gpu))
+    }
+
+    /// GPU 支持的最大 2D 纹理边长
+    pub fn gpu_max_texture_dimension(&self) -> u32 {
+        self.device.limits().max_texture_dimension_2d
+    }
+
+    /// 限制之后加载的图片使用的纹理边长（低内存 GPU 上以细节换稳定）；已上传的纹理不受影响
+    pub fn set_texture_limit(&mut self, limit: Option<u32>) {
+        self.texture_limit = limit;
+    }
+
+    /// 当前源纹理的尺寸（立方体贴图为单个面）；未加载图片时为 None
+    pub fn texture_size(&self) -> Option<(u32, u32)> {
+        match self.source.as_ref()? {
+            SourceImage::Equirect(_) => Some((self.texture.width(), self.texture.height())),
+            SourceImage::Cubemap(_) => Some((self.cube_texture.width(), self.cube_texture.height())),
+        }
+    }
+
+    /// 已加载全景的源纹理分辨率（纹素/度）
+    pub fn texels_per_degree(&self) -> Option<f32> {
+        self.texels_per_degree
+    }
+
+    /// 当前等矩形全景的 CPU 端像素，嵌入程序可以读取像素、生成缩略图或重新处理，不必重新解码。
+    /// 这份副本本来就为取色器和细节纹理保留，读取它不额外占用内存；但它会一直留到下一张图片就位
+    /// （8K×4K 约 128 MB，十亿像素级全景可达数 GB），需要时由调用方自行复制。
+    /// 内容是上传前处理后的画布：GPano 局部全景已补齐到完整画布，条幅全景则只有有效行（见 resample::expand_strip）；
+    /// 超过 GPU 限制时为全分辨率原图（纹理是它的缩小版），动图为当前帧。立方体贴图或未加载图片时为 None
+    pub fn source_image(&self) -> Option<&RgbaImage> {
+        match self.source.as_ref()? {
+            SourceImage::Equirect(img) => Some(img),
+            SourceImage::Cubemap(_) => None,
+        }
+    }
+
+    /// 当前立方体贴图的六个面（顺序同 load_cubemap），内存说明同 source_image；等矩形全景或未加载时为 None
+    pub fn source_cube_faces(&self) -> Option<&[RgbaImage; 6]> {
+        match self.source.as_ref()? {
+            SourceImage::Cubemap(faces) => Some(faces),
+            SourceImage::Equirect(_) => None,
+        }
+    }
 
-        self.camera_uniform.mode = match mode {
-            ProjectionMode::Rectilinear => 0,
-            ProjectionMode::Equidistant => 1,
-            ProjectionMode::Stereographic => 2,
-            ProjectionMode::Pannini => 3,
-            ProjectionMode::Equirectangular => 4,
-            ProjectionMode::Architectural => 5,
+    /// 世界方向上的源图像素（最近邻，不经过过滤与接缝羽化）；未加载图片时为 None
+    pub fn source_pixel(&self, dir: glam::Vec3) -> Option<[u8; 4]> {
+        let (image, uv) = match self.source.as_ref()? {
+            SourceImage::Equirect(img) => (img, crate::projection::direction_to_uv(dir)),
+            SourceImage::Cubemap(faces) => {
+                let (face, uv) = crate::cubemap::direction_to_face(dir);
+                (&faces[face], uv)
+            }
+        };
+        let (w, h) = image.dimensions();
+        let x = ((uv.x * w as f32) as u32).min(w.saturating_sub(1));
+        let y = match self.source.as_ref()? {
+            // 条幅全景纹理以外的行没有像素
+            SourceImage::Equirect(_) => {
+                let [v0, rows] = self.camera_uniform.tex_rows;
+                let v = (uv.y - v0) / rows;
+                if !(0.0..=1.0).contains(&v) {
+                    return None;
+                }
+                v
+            }
+            SourceImage::Cubemap(_) => uv.y,
         };
+        let y = ((y * h as f32) as u32).min(h.saturating_sub(1));
+        Some(image.get_pixel(x, y).0)
+    }
+
+    /// 当前全景的有效内容范围（局部全景），供视角软限位使用
+    pub fn coverage(&self) -> Option<Coverage> {
+        self.coverage
+    }
+
+    /// 当前窗口宽高比
+    pub fn aspect(&self) -> f32 {
+        self.camera_uniform.aspect
+    }
+
+    /// 设置 u=0/1 接缝两侧的羽化宽度（度，0 关闭）；下一次 update_camera 时写入 GPU
+    pub fn set_seam_blend(&mut self, width_deg: f32) {
+        self.camera_uniform.seam_blend = width_deg.max(0.0) / 360.0;
+    }
+
+    /// 新图片就位时是否淡入（减少动态效果时应关闭）；关闭时进行中的淡入立即结束
+    pub fn set_load_fade(&mut self, enabled: bool) {
+        self.load_fade_enabled = enabled;
+        if !enabled {
+            self.load_fade_start = None;
+        }
+    }
+
+    /// 设置暗角强度（0..1，0 关闭）；下一次 update_camera 时写入 GPU
+    pub fn set_vignette(&mut self, strength: f32) {
+        self.camera_uniform.vignette_strength = strength.clamp(0.0, 1.0);
+    }
+
+    /// 设置曝光补偿（EV，0 为不调整）；下一次 update_camera 时写入 GPU
+    pub fn set_exposure(&mut self, ev: f32) {
+        self.camera_uniform.exposure = 2f32.powf(ev);
+    }
 
-        self.queue
-            .write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[self.camera_uniform]));
+    /// 源图数据是否已经是线性值（不做 sRGB 解码）；下一次 update_camera 时写入 GPU
+    pub fn set_linear_source(&mut self, linear: bool) {
+        self.camera_uniform.linear_source = u32::from(linear);
+    }
+
+    /// 设置平面展开模式的平移量（度，见 PanoramaViewer3D::pan）；下一次 update_camera 时写入 GPU
+    pub fn set_pan(&mut self, pan_deg: [f32; 2]) {
+        self.camera_uniform.pan = pan_deg.map(f32::to_radians);
+    }
+
+    /// 设置水平校正（度，[pitch, roll]）；下一次 update_camera 时写入 GPU
+    pub fn set_alignment(&mut self, align_deg: [f32; 2]) {
+        self.camera_uniform.align_pitch = align_deg[0].to_radians();
+        self.camera_uniform.align_roll = align_deg[1].to_radians();
+    }
+
+    /// A/B 对比：右半边使用的投影，None 为关闭（只在光线投射路径下生效）；下一次 update_camera 时写入 GPU
+    pub fn set_split_mode(&mut self, mode: Option<ProjectionMode>) {
+        self.split_mode = mode;
+    }
+
+    /// 正在分屏对比时右半边的投影
+    fn active_split_mode(&self) -> Option<ProjectionMode> {
+        self.split_mode.filter(|_| self.render_path == RenderPath::RayCast)
+    }
+
+    pub fn update_camera(&mut self, yaw: f32, pitch: f32, fov: f32, mode: ProjectionMode) {
+        // 重要：安全夹取（fov 180° / pitch ±90° 的奇点）统一在 ViewParams::new 中完成，
+        // CPU 端的叠加层投影也使用同一份参数，保证与 shader 一致；UI 层仍可显示 180°。
+        let align = [self.camera_uniform.align_pitch, self.camera_uniform.align_roll].map(f32::to_degrees);
+        let params = ViewParams::new(yaw, pitch, fov, mode, self.camera_uniform.aspect)
+            .with_pan(self.camera_uniform.pan.map(f32::to_degrees))
+            .with_alignment(align);
+
+        self.camera_uniform.yaw = params.yaw;
+        self.camera_uniform.pitch = params.pitch;
+        self.camera_uniform.fov_rad = params.fov_rad;
+
+        self.camera_uniform.mode = mode_index(mode);
+
+        // 淡入按时间推进，每帧随相机参数一起写入
+        let fade = self.load_fade_start.map_or(1.0, |start| {
+            (start.elapsed().as_secs_f32() / LOAD_FADE_DURATION.as_secs_f32()).min(1.0)
+        });
+        if fade >= 1.0 {
+            self.load_fade_start = None;
+        }
+        self.camera_uniform.load_fade = fade;
+
+        self.update_detail(&params);
+
+        match self.active_split_mode() {
+            // 分屏：左右各占半个窗口（见 draw_scene），宽高比减半；视线方向与 FOV 两边相同
+            Some(split_mode) => {
+                let half_aspect = self.camera_uniform.aspect * 0.5;
+                let mut left = self.camera_uniform;
+                left.aspect = half_aspect;
+                let split = ViewParams::new(yaw, pitch, fov, split_mode, half_aspect)
+                    .with_pan(self.camera_uniform.pan.map(f32::to_degrees))
+                    .with_alignment(align);
+                let right = CameraUniform {
+                    yaw: split.yaw,
+                    pitch: split.pitch,
+                    fov_rad: split.fov_rad,
+                    mode: mode_index(split_mode),
+                    ..left
+                };
+                self.queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[left]));
+                self.queue.write_buffer(&self.split_camera_buffer, 0, bytemuck::cast_slice(&[right]));
+            }
+            None => {
+                self.queue
+                    .write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[self.camera_uniform]));
+            }
+        }
+
+        if let (Some(mesh), RenderPath::Mesh) = (&self.mesh_renderer, self.render_path) {
+            // 网格路径只有透视相机：按 Rectilinear 的规则夹取 FOV
+            let params = ViewParams::new(yaw, pitch, fov, ProjectionMode::Rectilinear, self.camera_uniform.aspect)
+                .with_alignment(align);
+            mesh.update_camera(&self.queue, &params);
+        }
     }
 
     pub fn load_panorama(&mut self, img: RgbaImage) {
-        // 获取 GPU 纹理尺寸限制
-        let max_texture_dimension = self.device.limits().max_texture_dimension_2d;
-        
-        let (src_w, src_h) = img.dimensions();
-        
-        // 如果图片超过 GPU 限制，则缩放到限制内
-        let img = if src_w > max_texture_dimension || src_h > max_texture_dimension {
-            let scale = (max_texture_dimension as f32 / src_w.max(src_h) as f32).min(1.0);
-            let new_w = (src_w as f32 * scale) as u32;
-            let new_h = (src_h as f32 * scale) as u32;
-            eprintln!(
-                "{}",
-                crate::i18n::tr_with(
-                    "gpu.image_too_large_scaled",
-                    &[
-                        ("src_w", src_w.to_string()),
-                        ("src_h", src_h.to_string()),
-                        ("max", max_texture_dimension.to_string()),
-                        ("new_w", new_w.to_string()),
-                        ("new_h", new_h.to_string())
-                    ]
-                )
-            );
-            image::DynamicImage::ImageRgba8(img).resize(
-                new_w,
-                new_h,
-                image::imageops::FilterType::Lanczos3
-            ).to_rgba8()
-        } else {
-            img
-        };
-        
-        // 兼容非 2:1 纹理：
-        // - 以"宽度"为基准计算目标等矩形高度 target_h = width / 2
-        // - 如果原图高度 < target_h：在顶部补黑，把原图贴到底部（上方空置）
-        // 这样 shader 在采样 v=0..1 时，上半部分自然是黑色。
-        let (src_w, src_h) = img.dimensions();
-        let target_h = src_w / 2;
-
-        let img = if target_h > 0 && src_h < target_h {
-            let mut canvas = RgbaImage::from_pixel(src_w, target_h, Rgba([0, 0, 0, 255]));
-            let y_offset = target_h - src_h;
-            // 把原图贴到底部
-            // copy_from 在越界时会返回 Err，这里 y_offset 已保证不会越界
-            let _ = canvas.copy_from(&img, 0, y_offset);
-            canvas
-        } else {
-            img
+        self.load_partial_panorama(img, None);
+    }
+
+    /// 上传等矩形全景；crop 为 GPano 裁切信息时把图片放回完整画布中的对应位置
+    pub fn load_partial_panorama(&mut self, img: RgbaImage, crop: Option<GPanoCrop>) {
+        let (img, coverage) = crate::resample::prepare_equirect(img, crop, u32::MAX, false);
+        match crate::resample::downscale_to_fit(&img, self.max_texture_dimension()) {
+            Some(base) => self.show_equirect(base, Some(img), coverage),
+            None => self.show_equirect(img, None, coverage),
+        }
+    }
+
+    /// 立即上传已经过 resample::prepare_equirect 处理的等矩形纹理
+    pub fn load_prepared_panorama(&mut self, img: &RgbaImage, coverage: Option<Coverage>) {
+        self.show_equirect(img.clone(), None, coverage);
+    }
+
+    fn show_equirect(&mut self, img: RgbaImage, full_resolution: Option<RgbaImage>, coverage: Option<Coverage>) {
+        self.upload = None;
+        self.texture = self.create_equirect_texture(img.dimensions());
+        write_rows(&self.queue, &self.texture, &img, 0, img.height());
+        self.finish_equirect(img, full_resolution, coverage);
+    }
+
+    /// 分帧上传已处理好的等矩形纹理：每次 continue_upload 只写入一部分行，
+    /// 上传期间继续显示旧图、事件循环不被大块拷贝阻塞；全部写完后才切换到新纹理。
+    /// full_resolution 为超过 GPU 限制的原图（img 是其缩小版），放大时从中裁出细节纹理
+    pub fn begin_prepared_upload(
+        &mut self,
+        img: RgbaImage,
+        full_resolution: Option<RgbaImage>,
+        coverage: Option<Coverage>,
+    ) {
+        let texture = self.create_equirect_texture(img.dimensions());
+        self.upload = Some(PendingUpload { texture, image: img, next_row: 0, coverage, full_resolution });
+    }
+
+    /// 继续进行中的分帧上传；本次调用完成上传（新纹理已生效）时返回 true
+    pub fn continue_upload(&mut self) -> bool {
+        let Some(upload) = self.upload.as_mut() else {
+            return false;
         };
+        let (width, height) = upload.image.dimensions();
+        let rows = (UPLOAD_BUDGET_BYTES / (4 * width as usize)).max(1) as u32;
+        let end = (upload.next_row + rows).min(height);
+        write_rows(&self.queue, &upload.texture, &upload.image, upload.next_row, end);
+        upload.next_row = end;
+        if end < height {
+            return false;
+        }
 
-        let (width, height) = img.dimensions();
-        let texture_size = wgpu::Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
+        let Some(upload) = self.upload.take() else {
+            return false;
         };
+        self.texture = upload.texture;
+        self.finish_equirect(upload.image, upload.full_resolution, upload.coverage);
+        true
+    }
 
-        self.texture = self.device.create_texture(&wgpu::TextureDescriptor {
-            size: texture_size,
+    /// 是否有尚未完成的分帧上传
+    pub fn is_uploading(&self) -> bool {
+        self.upload.is_some()
+    }
+
+    /// 用同尺寸的新一帧覆盖当前等矩形纹理（动图播放），尺寸不符时忽略
+    pub fn update_panorama_frame(&mut self, img: &RgbaImage) {
+        let size = self.texture.size();
+        if img.dimensions() != (size.width, size.height) {
+            return;
+        }
+        write_rows(&self.queue, &self.texture, img, 0, size.height);
+        if let Some(SourceImage::Equirect(source)) = self.source.as_mut() {
+            if source.dimensions() == img.dimensions() {
+                source.copy_from_slice(img);
+            }
+        }
+    }
+
+    fn create_equirect_texture(&self, (width, height): (u32, u32)) -> wgpu::Texture {
+        self.device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
@@ -546,55 +1130,317 @@ impl Renderer {
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             label: Some("panorama_texture"),
             view_formats: &[],
+        })
+    }
+
+    /// 新的等矩形纹理就位后更新相关状态；img 为刚上传的像素，与全分辨率原图（如有）
+    /// 二者之一留作取色器和细节纹理的数据源
+    fn finish_equirect(&mut self, img: RgbaImage, full_resolution: Option<RgbaImage>, coverage: Option<Coverage>) {
+        let source = full_resolution.unwrap_or(img);
+        // 等矩形：整幅宽度对应 360°；有细节纹理时放大后能看到原图分辨率
+        self.texels_per_degree = Some(source.width() as f32 / 360.0);
+        self.source = Some(SourceImage::Equirect(source));
+        self.coverage = coverage;
+        self.camera_uniform.tex_rows = match coverage {
+            Some(c) if c.strip => [c.pitch.0 / 180.0 + 0.5, (c.pitch.1 - c.pitch.0) / 180.0],
+            _ => [0.0, 1.0],
+        };
+        self.clear_detail();
+        self.load_fade_start = self.load_fade_enabled.then(Instant::now);
+
+        // 释放之前的立方体贴图（wgpu 会把未写入的纹理清零）
+        self.cube_texture = create_cube_texture(&self.device, 1);
+        self.camera_uniform.source = 0;
+        self.rebuild_bind_group();
+    }
+
+    /// 上传立方体贴图的六个面（顺序 +X, -X, +Y, -Y, +Z, -Z，见 cubemap.rs）
+    pub fn load_cubemap(&mut self, faces: [RgbaImage; 6]) {
+        let faces = crate::resample::fit_cube_faces(faces, self.max_texture_dimension());
+
+        let size = faces[0].width();
+        // 立方体贴图：每个面的边长对应 90°
+        self.texels_per_degree = Some(size as f32 / 90.0);
+        self.coverage = None;
+        self.cube_texture = create_cube_texture(&self.device, size);
+        for (layer, face) in faces.iter().enumerate() {
+            self.queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &self.cube_texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d { x: 0, y: 0, z: layer as u32 },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                face,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * size),
+                    rows_per_image: Some(size),
+                },
+                wgpu::Extent3d { width: size, height: size, depth_or_array_layers: 1 },
+            );
+        }
+
+        // 同样释放之前的等矩形纹理
+        self.upload = None;
+        self.texture = self.create_equirect_texture((1, 1));
+        self.clear_detail();
+        self.load_fade_start = self.load_fade_enabled.then(Instant::now);
+        self.camera_uniform.source = 1;
+        self.rebuild_bind_group();
+        self.source = Some(SourceImage::Cubemap(Box::new(faces)));
+    }
+
+    /// 释放细节纹理（调用方随后重建 bind group）
+    fn clear_detail(&mut self) {
+        self.detail_texture = create_detail_texture(&self.device, 1, 1);
+        self.detail_rect = None;
+        self.camera_uniform.detail = [0.0; 4];
+    }
+
+    /// 超大等矩形图：放大到缩小版纹理不够清晰时，从全分辨率原图裁出当前可见范围
+    /// （四周留 DETAIL_MARGIN 余量）上传为细节纹理；视野仍在已上传范围内时不重新裁剪。
+    /// 可见范围放不进一张纹理（视野太大）时直接使用缩小版
+    fn update_detail(&mut self, params: &ViewParams) {
+        let Some(SourceImage::Equirect(full)) = self.source.as_ref() else {
+            return;
+        };
+        let (full_w, full_h) = full.dimensions();
+        let base_w = self.texture.width();
+        if full_w <= base_w {
+            return;
+        }
+
+        let screen_per_radian = crate::projection::center_pixels_per_radian(params, self.size.height as f32);
+        let base_per_radian = base_w as f32 / std::f32::consts::TAU;
+        let max = self.max_texture_dimension();
+        // 可见范围换算到纹理自身的 v（条幅全景的纹理只含部分行）
+        let visible = crate::projection::visible_uv_rect(params);
+        let [v0, rows] = self.camera_uniform.tex_rows;
+        let visible = crate::projection::UvRect { v0: (visible.v0 - v0) / rows, height: visible.height / rows, ..visible };
+        let to_px = |rect: crate::projection::UvRect| {
+            let x0 = (rect.u0 * full_w as f32).floor();
+            let x1 = ((rect.u0 + rect.width) * full_w as f32).ceil();
+            let y0 = (rect.v0 * full_h as f32).floor().clamp(0.0, full_h as f32);
+            let y1 = ((rect.v0 + rect.height) * full_h as f32).ceil().clamp(0.0, full_h as f32);
+            let x = (x0 as i64).rem_euclid(full_w as i64) as u32;
+            let w = ((x1 - x0) as u32).clamp(1, full_w);
+            [x, y0 as u32, w, ((y1 - y0) as u32).max(1)]
+        };
+        let needed = to_px(visible);
+        if screen_per_radian <= base_per_radian || needed[2] > max || needed[3] > max {
+            self.camera_uniform.detail = [0.0; 4];
+            return;
+        }
+
+        let contains = |[x, y, w, h]: [u32; 4]| {
+            let dx = (needed[0] + full_w - x) % full_w;
+            dx + needed[2] <= w && needed[1] >= y && needed[1] + needed[3] <= y + h
+        };
+        let rect = match self.detail_rect {
+            Some(rect) if contains(rect) => rect,
+            _ => {
+                let margin_u = visible.width * DETAIL_MARGIN;
+                let margin_v = visible.height * DETAIL_MARGIN;
+                let mut rect = to_px(crate::projection::UvRect {
+                    u0: visible.u0 - margin_u,
+                    v0: visible.v0 - margin_v,
+                    width: visible.width + 2.0 * margin_u,
+                    height: visible.height + 2.0 * margin_v,
+                });
+                // 余量放不下时缩小余量，保持可见范围居中
+                if rect[2] > max {
+                    let shift = (max - needed[2]) / 2;
+                    rect[0] = (needed[0] + full_w - shift) % full_w;
+                    rect[2] = max;
+                }
+                if rect[3] > max {
+                    rect[1] = needed[1].saturating_sub((max - needed[3]) / 2).min(full_h - max);
+                    rect[3] = max;
+                }
+                let [x, y, w, h] = rect;
+                let crop = crate::resample::crop_wrapped(full, x, y, w, h);
+                if self.detail_texture.width() != w || self.detail_texture.height() != h {
+                    self.detail_texture = create_detail_texture(&self.device, w, h);
+                    self.rebuild_bind_group();
+                }
+                write_rows(&self.queue, &self.detail_texture, &crop, 0, h);
+                self.detail_rect = Some(rect);
+                rect
+            }
+        };
+        let [x, y, w, h] = rect;
+        self.camera_uniform.detail =
+            [x as f32 / full_w as f32, y as f32 / full_h as f32, w as f32 / full_w as f32, h as f32 / full_h as f32];
+    }
+
+    // 纹理替换后重建 bind group
+    fn rebuild_bind_group(&mut self) {
+        self.diffuse_bind_group = create_bind_group(
+            &self.device,
+            &self.texture_bind_group_layout,
+            &self.camera_buffer,
+            &self.texture,
+            &self.cube_texture,
+            &self.detail_texture,
+            &self.sampler,
+        );
+        self.split_bind_group = create_bind_group(
+            &self.device,
+            &self.texture_bind_group_layout,
+            &self.split_camera_buffer,
+            &self.texture,
+            &self.cube_texture,
+            &self.detail_texture,
+            &self.sampler,
+        );
+    }
+
+    fn draw_scene(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.1, g: 0.1, b: 0.1, a: 1.0 }),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+
+        match (&self.mesh_renderer, self.render_path) {
+            (Some(mesh), RenderPath::Mesh) => mesh.draw(&mut render_pass, &self.diffuse_bind_group),
+            _ => {
+                render_pass.set_pipeline(&self.render_pipeline);
+                render_pass.set_bind_group(0, &self.diffuse_bind_group, &[]);
+                if self.active_split_mode().is_some() {
+                    // 分屏：同一个全屏三角形分别画进左右两个视口，右半边使用 split_bind_group
+                    let (width, height) = (self.config.width as f32, self.config.height as f32);
+                    let half = (width * 0.5).floor();
+                    render_pass.set_viewport(0.0, 0.0, half, height, 0.0, 1.0);
+                    render_pass.draw(0..3, 0..1);
+                    render_pass.set_bind_group(0, &self.split_bind_group, &[]);
+                    render_pass.set_viewport(half, 0.0, width - half, height, 0.0, 1.0);
+                }
+                render_pass.draw(0..3, 0..1); // Draw 3 vertices for fullscreen coverage
+            }
+        }
+    }
+
+    /// 与窗口表面同尺寸、同格式的离屏渲染目标
+    fn create_offscreen_target(&self) -> wgpu::Texture {
+        self.device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d { width: self.config.width, height: self.config.height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            label: Some("offscreen_texture"),
+            view_formats: &[],
+        })
+    }
+
+    /// 离屏连续渲染 frames 帧当前视图（不含界面），返回平均每帧耗时；
+    /// 计时到 GPU 完成最后一帧为止（见 bench.rs）
+    pub fn bench_frames(&self, frames: u32) -> Duration {
+        let target = self.create_offscreen_target();
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let start = Instant::now();
+        for _ in 0..frames {
+            let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Bench Encoder"),
+            });
+            self.draw_scene(&mut encoder, &view);
+            self.queue.submit(std::iter::once(encoder.finish()));
+        }
+        self.device.poll(wgpu::Maintain::Wait);
+        start.elapsed() / frames.max(1)
+    }
+
+    /// 以窗口分辨率离屏渲染当前视图（不含界面）并读回 CPU
+    pub fn capture_frame(&self) -> Option<RgbaImage> {
+        let (width, height) = (self.config.width, self.config.height);
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let target = self.create_offscreen_target();
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // copy_texture_to_buffer 要求每行字节数按 256 对齐
+        let unpadded_row = 4 * width;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_row = unpadded_row.div_ceil(align) * align;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("capture_buffer"),
+            size: padded_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
         });
 
-        self.queue.write_texture(
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Capture Encoder"),
+        });
+        self.draw_scene(&mut encoder, &view);
+        encoder.copy_texture_to_buffer(
             wgpu::ImageCopyTexture {
-                texture: &self.texture,
+                texture: &target,
                 mip_level: 0,
                 origin: wgpu::Origin3d::ZERO,
                 aspect: wgpu::TextureAspect::All,
             },
-            &img,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * width),
-                rows_per_image: Some(height),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(height),
+                },
             },
-            texture_size,
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
         );
+        self.queue.submit(std::iter::once(encoder.finish()));
 
-        let texture_view = self.texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        // Recreate bind group with new texture view
-        self.diffuse_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &self.texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: self.camera_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::TextureView(&texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: wgpu::BindingResource::Sampler(&self.sampler),
-                },
-            ],
-            label: Some("diffuse_bind_group"),
+        let slice = buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |r| {
+            let _ = tx.send(r);
         });
-    }
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv().ok()?.ok()?;
 
-    
+        // 表面格式可能是 BGRA：统一转成 RGBA（sRGB 编码原样保留）
+        let bgra = matches!(
+            self.config.format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+        let mut pixels = Vec::with_capacity((unpadded_row * height) as usize);
+        for row in slice.get_mapped_range().chunks(padded_row as usize) {
+            for px in row[..unpadded_row as usize].chunks_exact(4) {
+                if bgra {
+                    pixels.extend_from_slice(&[px[2], px[1], px[0], 255]);
+                } else {
+                    pixels.extend_from_slice(&[px[0], px[1], px[2], 255]);
+                }
+            }
+        }
+        buffer.unmap();
+
+        RgbaImage::from_raw(width, height, pixels)
+    }
 
     pub fn render_with_ui(
         &mut self, 
         window: &Window, 
         run_ui: impl FnOnce(&egui::Context)
     ) -> Result<(), wgpu::SurfaceError> {
-        let output = self.surface.get_current_texture()?;
+        let frame_start = Instant::now();
+        let output = self.surface.get_current_texture().inspect_err(|e| {
+            self.frame_stats.last_surface_error = Some((e.clone(), frame_start));
+        })?;
         let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -602,24 +1448,7 @@ impl Renderer {
         });
 
         // 1. Render Scene (Fullscreen Quad)
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.1, g: 0.1, b: 0.1, a: 1.0 }),
-                        store: true,
-                    },
-                })],
-                depth_stencil_attachment: None,
-            });
-
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_bind_group(0, &self.diffuse_bind_group, &[]);
-            render_pass.draw(0..3, 0..1); // Draw 3 vertices for fullscreen coverage
-        }
+        self.draw_scene(&mut encoder, &view);
         
         // 2. Render UI
         let raw_input = self.egui_state.take_egui_input(window);
@@ -662,9 +1491,20 @@ impl Renderer {
             self.egui_renderer.free_texture(id);
         }
 
+        let submit_start = Instant::now();
         self.queue.submit(std::iter::once(encoder.finish()));
         output.present();
+        self.frame_stats.submit_time = submit_start.elapsed();
+        self.frame_stats.cpu_time = frame_start.elapsed();
 
         Ok(())
     }
+
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.frame_stats
+    }
+
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.config.present_mode
+    }
 }
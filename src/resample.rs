@@ -0,0 +1,552 @@
+// resample.rs — 多线程 Lanczos3 缩放
+//
+// 算法与 image::imageops::resize(.., FilterType::Lanczos3) 逐步一致：
+// 先垂直后水平、f32 中间结果、相同的权重归一化与取整方式。
+// 区别只在于把互不依赖的输出行分给 rayon 线程池，因此结果与单线程路径逐字节相同。
+//
+// 这里也放上传前的纯 CPU 处理（缩放到 GPU 纹理限制、补齐 2:1 画布），
+// 由后台加载线程调用，主线程只负责把结果拷贝到 GPU。
+
+use crate::metadata::GPanoCrop;
+use crate::panorama::Coverage;
+use image::{DynamicImage, Rgba, RgbaImage};
+use rayon::prelude::*;
+
+const SUPPORT: f32 = 3.0;
+
+fn sinc(t: f32) -> f32 {
+    let a = t * std::f32::consts::PI;
+    if t == 0.0 {
+        1.0
+    } else {
+        a.sin() / a
+    }
+}
+
+fn lanczos3(x: f32) -> f32 {
+    if x.abs() < SUPPORT {
+        sinc(x) * sinc(x / SUPPORT)
+    } else {
+        0.0
+    }
+}
+
+/// 每个输出坐标对应的输入区间起点与归一化权重
+fn weights(src_len: u32, dst_len: u32) -> Vec<(u32, Vec<f32>)> {
+    let ratio = src_len as f32 / dst_len as f32;
+    let sratio = if ratio < 1.0 { 1.0 } else { ratio };
+    let src_support = SUPPORT * sratio;
+
+    (0..dst_len)
+        .map(|out| {
+            let input = (out as f32 + 0.5) * ratio;
+
+            let left = ((input - src_support).floor() as i64).clamp(0, src_len as i64 - 1) as u32;
+            let right = ((input + src_support).ceil() as i64)
+                .clamp(left as i64 + 1, src_len as i64) as u32;
+
+            let input = input - 0.5;
+            let mut ws = Vec::with_capacity((right - left) as usize);
+            let mut sum = 0.0;
+            for i in left..right {
+                let w = lanczos3((i as f32 - input) / sratio);
+                ws.push(w);
+                sum += w;
+            }
+            ws.iter_mut().for_each(|w| *w /= sum);
+            (left, ws)
+        })
+        .collect()
+}
+
+/// 解码结果转 RGBA8；最常见的 RGB8（JPEG）按行并行转换，其余格式交给 image
+/// （16 位 PNG / TIFF 与浮点 TIFF 在这里按比例缩到 8 位）
+pub fn into_rgba8(img: DynamicImage) -> RgbaImage {
+    match img {
+        DynamicImage::ImageRgb8(rgb) => {
+            let (w, h) = rgb.dimensions();
+            let mut out = vec![0u8; w as usize * h as usize * 4];
+            out.par_chunks_mut(w as usize * 4)
+                .zip(rgb.as_raw().par_chunks(w as usize * 3))
+                .for_each(|(dst, src)| {
+                    for (d, s) in dst.chunks_exact_mut(4).zip(src.chunks_exact(3)) {
+                        d[..3].copy_from_slice(s);
+                        d[3] = 255;
+                    }
+                });
+            RgbaImage::from_raw(w, h, out).expect("buffer size matches dimensions")
+        }
+        other => other.into_rgba8(),
+    }
+}
+
+/// 按 EXIF Orientation 把图片转成正常显示的方向（与其它看图软件一致）；1 和未知值原样返回。
+/// 5~8 会交换宽高
+pub fn apply_exif_orientation(img: RgbaImage, orientation: u32) -> RgbaImage {
+    use image::imageops;
+    match orientation {
+        2 => imageops::flip_horizontal(&img),
+        3 => imageops::rotate180(&img),
+        4 => imageops::flip_vertical(&img),
+        // 沿主对角线翻转（转置）
+        5 => imageops::flip_horizontal(&imageops::rotate90(&img)),
+        6 => imageops::rotate90(&img),
+        // 沿副对角线翻转
+        7 => imageops::flip_horizontal(&imageops::rotate270(&img)),
+        8 => imageops::rotate270(&img),
+        _ => img,
+    }
+}
+
+/// 等比缩放到不超过 (max_w, max_h) 的尺寸（与 DynamicImage::resize 的尺寸计算相同）
+pub fn fit_dimensions(width: u32, height: u32, max_w: u32, max_h: u32) -> (u32, u32) {
+    let ratio = f64::min(max_w as f64 / width as f64, max_h as f64 / height as f64);
+    let w = ((width as f64 * ratio).round() as u64).clamp(1, u32::MAX as u64) as u32;
+    let h = ((height as f64 * ratio).round() as u64).clamp(1, u32::MAX as u64) as u32;
+    (w, h)
+}
+
+/// sRGB 编码值（0..=1）-> 线性光
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// 线性光 -> sRGB 编码值（0..=1）
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// 自动曝光能提亮的上限（EV）
+pub const AUTO_EXPOSURE_MAX_EV: f32 = 3.0;
+
+/// 自动曝光：让画面亮度的中位数落到中灰（线性 0.18）所需的 EV，限制在 0..=AUTO_EXPOSURE_MAX_EV
+///
+/// 只提亮不压暗，正常或偏亮的图片得到 0。亮度在线性光下计算，直方图按 sRGB 编码值分 256 档
+/// （暗部分辨率更高）；大图按固定步长抽样约 64K 个像素，耗时可以忽略。
+pub fn auto_exposure_ev(images: &[&RgbaImage]) -> f32 {
+    const SAMPLES: usize = 1 << 16;
+    const MID_GREY: f32 = 0.18;
+
+    let lut: Vec<f32> = (0..=255).map(|v| srgb_to_linear(v as f32 / 255.0)).collect();
+    let pixels: usize = images.iter().map(|img| img.as_raw().len() / 4).sum();
+    let step = (pixels / SAMPLES).max(1);
+    let mut histogram = [0usize; 256];
+    for img in images {
+        for px in img.as_raw().chunks_exact(4).step_by(step) {
+            let y = 0.2126 * lut[px[0] as usize] + 0.7152 * lut[px[1] as usize] + 0.0722 * lut[px[2] as usize];
+            histogram[(linear_to_srgb(y) * 255.0).round() as usize] += 1;
+        }
+    }
+
+    let total: usize = histogram.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let mut seen = 0;
+    let median = histogram
+        .iter()
+        .position(|&n| {
+            seen += n;
+            seen * 2 >= total
+        })
+        .unwrap_or(0);
+    let median = lut[median].max(MID_GREY / 2f32.powf(AUTO_EXPOSURE_MAX_EV));
+    (MID_GREY / median).log2().clamp(0.0, AUTO_EXPOSURE_MAX_EV)
+}
+
+/// 多线程 Lanczos3 缩放到精确尺寸
+pub fn resize_lanczos3(img: &RgbaImage, new_w: u32, new_h: u32) -> RgbaImage {
+    let (width, height) = img.dimensions();
+    if (new_w, new_h) == (width, height) {
+        return img.clone();
+    }
+
+    let src = img.as_raw();
+    let row = width as usize * 4;
+
+    // 1) 垂直方向：输出每一行只依赖输入的若干行
+    let v_weights = weights(height, new_h);
+    let mut tmp = vec![0.0f32; row * new_h as usize];
+    tmp.par_chunks_mut(row)
+        .zip(v_weights.par_iter())
+        .for_each(|(out_row, (top, ws))| {
+            for x in 0..width as usize {
+                let mut t = [0.0f32; 4];
+                for (i, w) in ws.iter().enumerate() {
+                    let base = (*top as usize + i) * row + x * 4;
+                    for c in 0..4 {
+                        t[c] += src[base + c] as f32 * w;
+                    }
+                }
+                out_row[x * 4..x * 4 + 4].copy_from_slice(&t);
+            }
+        });
+
+    // 2) 水平方向：输出每一行只依赖中间结果的同一行
+    let h_weights = weights(width, new_w);
+    let out_row_len = new_w as usize * 4;
+    let mut out = vec![0u8; out_row_len * new_h as usize];
+    out.par_chunks_mut(out_row_len)
+        .zip(tmp.par_chunks(row))
+        .for_each(|(out_row, in_row)| {
+            for (outx, (left, ws)) in h_weights.iter().enumerate() {
+                let mut t = [0.0f32; 4];
+                for (i, w) in ws.iter().enumerate() {
+                    let base = (*left as usize + i) * 4;
+                    for c in 0..4 {
+                        t[c] += in_row[base + c] * w;
+                    }
+                }
+                for c in 0..4 {
+                    out_row[outx * 4 + c] = t[c].clamp(0.0, 255.0).round() as u8;
+                }
+            }
+        });
+
+    RgbaImage::from_raw(new_w, new_h, out).expect("buffer size matches dimensions")
+}
+
+/// 没有 GPano 信息、宽度至少为高度这么多倍（垂直视角不超过 90°）的条幅全景不补齐画布：
+/// 补成 2:1 会分配数倍乃至数十倍于原图的黑边（36000×200 -> 36000×18000），
+/// 纹理只保存有效行，由 shader 按 Coverage 把 v 映射到纹理行
+const STRIP_MIN_ASPECT: u64 = 4;
+
+/// 等矩形图片在完整 2:1 画布中的位置（像素）
+struct PanoramaLayout {
+    canvas: (u32, u32),
+    offset: (u32, u32),
+    size: (u32, u32),
+    /// 条幅全景：画布只用于计算位置，不实际补齐
+    strip: bool,
+}
+
+impl PanoramaLayout {
+    fn new(width: u32, height: u32, crop: Option<GPanoCrop>) -> Self {
+        let (canvas, offset) = match crop {
+            Some(c) => {
+                // 图片可能已被缩放：按实际宽度与 GPano 裁切宽度的比例换算
+                let s = width as f64 / c.width as f64;
+                let px = |v: u32| (v as f64 * s).round() as u32;
+                let canvas_w = px(c.full_width).max(width);
+                let top = px(c.top);
+                let canvas_h = px(c.full_height).max(top + height);
+                ((canvas_w, canvas_h), (px(c.left) % canvas_w, top))
+            }
+            None => {
+                let canvas_h = height.max(width / 2);
+                ((width, canvas_h), (0, canvas_h - height))
+            }
+        };
+        let strip = crop.is_none() && width as u64 >= height as u64 * STRIP_MIN_ASPECT;
+        Self { canvas, offset, size: (width, height), strip }
+    }
+
+    /// 有效内容范围；画布被完全覆盖时为 None
+    fn coverage(&self) -> Option<Coverage> {
+        let (cw, ch) = (self.canvas.0 as f32, self.canvas.1 as f32);
+        let (x, y) = (self.offset.0 as f32, self.offset.1 as f32);
+        let (w, h) = (self.size.0 as f32, self.size.1 as f32);
+
+        // u=0.5 对应 yaw 0，v=0.5 对应 pitch 0；pitch 向下为正
+        let yaw = (w < cw).then(|| ((x / cw - 0.5) * 360.0, ((x + w) / cw - 0.5) * 360.0));
+        let pitch = ((y / ch - 0.5) * 180.0, ((y + h) / ch - 0.5) * 180.0);
+        let full_pitch = h >= ch;
+
+        (yaw.is_some() || !full_pitch).then_some(Coverage { yaw, pitch, strip: self.strip })
+    }
+}
+
+/// 等矩形图片补齐后实际分配的画布尺寸；条幅全景不补齐，为原尺寸
+pub fn padded_dimensions(width: u32, height: u32, crop: Option<GPanoCrop>) -> (u32, u32) {
+    let layout = PanoramaLayout::new(width, height, crop);
+    if layout.strip {
+        (width, height)
+    } else {
+        layout.canvas
+    }
+}
+
+/// 把等矩形图片缩放到 GPU 纹理限制内并补齐到完整画布，返回纹理像素与有效内容范围
+pub fn prepare_equirect(
+    img: RgbaImage,
+    crop: Option<GPanoCrop>,
+    max_texture_dimension: u32,
+    log_scaling: bool,
+) -> (RgbaImage, Option<Coverage>) {
+    let (src_w, src_h) = img.dimensions();
+    let layout = PanoramaLayout::new(src_w, src_h, crop);
+
+    // 如果补齐后的画布超过 GPU 限制，则把原图等比缩放到画布能放进限制内
+    let canvas_max = layout.canvas.0.max(layout.canvas.1);
+    let img = if canvas_max > max_texture_dimension {
+        let scale = max_texture_dimension as f64 / canvas_max as f64;
+        let new_w = ((src_w as f64 * scale).floor() as u32).max(1);
+        let new_h = ((src_h as f64 * scale).floor() as u32).max(1);
+        if log_scaling {
+            log::warn!(
+                "{}",
+                crate::i18n::tr_with(
+                    "gpu.image_too_large_scaled",
+                    &[
+                        ("src_w", src_w.to_string()),
+                        ("src_h", src_h.to_string()),
+                        ("max", max_texture_dimension.to_string()),
+                        ("new_w", new_w.to_string()),
+                        ("new_h", new_h.to_string())
+                    ]
+                )
+            );
+        }
+        // 与 DynamicImage::resize(.., Lanczos3) 结果相同，按行多线程
+        resize_lanczos3(&img, new_w, new_h)
+    } else {
+        img
+    };
+
+    // 兼容非 2:1 纹理：
+    // - 有 GPano 裁切信息时按其位置贴到完整画布上（水平方向可能跨越接缝）
+    // - 否则以"宽度"为基准计算目标等矩形高度 target_h = width / 2，
+    //   原图高度不足时在顶部补黑，把原图贴到底部（上方空置）
+    // 这样 shader 在采样 v=0..1 时，缺失部分自然是黑色。
+    // - 条幅全景（STRIP_MIN_ASPECT）位置相同但不补黑边，shader 把纹理行以外显示为黑色
+    let (src_w, src_h) = img.dimensions();
+    let layout = PanoramaLayout::new(src_w, src_h, crop);
+    let coverage = layout.coverage();
+
+    let img = if layout.canvas != (src_w, src_h) && !layout.strip {
+        let (canvas_w, canvas_h) = layout.canvas;
+        let mut canvas = RgbaImage::from_pixel(canvas_w, canvas_h, Rgba([0, 0, 0, 255]));
+        let (x, y) = (layout.offset.0 as i64, layout.offset.1 as i64);
+        // replace 会裁掉越界部分；跨越右边缘的部分从左边缘再贴一次
+        image::imageops::replace(&mut canvas, &img, x, y);
+        if x + src_w as i64 > canvas_w as i64 {
+            image::imageops::replace(&mut canvas, &img, x - canvas_w as i64, y);
+        }
+        canvas
+    } else {
+        img
+    };
+
+    (img, coverage)
+}
+
+/// 把条幅全景（Coverage::strip）补齐成完整的 2:1 画布，pitch 为其垂直范围（度）
+pub fn expand_strip(img: &RgbaImage, pitch: (f32, f32)) -> RgbaImage {
+    let (w, h) = img.dimensions();
+    let canvas_h = (w / 2).max(h);
+    let top = ((pitch.0 / 180.0 + 0.5) * canvas_h as f32).round() as i64;
+    let mut canvas = RgbaImage::from_pixel(w, canvas_h, Rgba([0, 0, 0, 255]));
+    image::imageops::replace(&mut canvas, img, 0, top.clamp(0, (canvas_h - h) as i64));
+    canvas
+}
+
+/// 立方体贴图的面超过 GPU 纹理限制时缩小（六个面尺寸相同）
+pub fn fit_cube_faces(faces: [RgbaImage; 6], max_texture_dimension: u32) -> [RgbaImage; 6] {
+    let src_size = faces[0].width();
+    if src_size <= max_texture_dimension {
+        return faces;
+    }
+
+    log::warn!(
+        "{}",
+        crate::i18n::tr_with(
+            "gpu.image_too_large_scaled",
+            &[
+                ("src_w", src_size.to_string()),
+                ("src_h", src_size.to_string()),
+                ("max", max_texture_dimension.to_string()),
+                ("new_w", max_texture_dimension.to_string()),
+                ("new_h", max_texture_dimension.to_string())
+            ]
+        )
+    );
+    faces.map(|f| resize_lanczos3(&f, max_texture_dimension, max_texture_dimension))
+}
+
+/// 图片超过 GPU 纹理限制时返回等比缩小后的副本，否则返回 None
+pub fn downscale_to_fit(img: &RgbaImage, max_texture_dimension: u32) -> Option<RgbaImage> {
+    let (src_w, src_h) = img.dimensions();
+    let src_max = src_w.max(src_h);
+    if src_max <= max_texture_dimension {
+        return None;
+    }
+
+    let scale = max_texture_dimension as f64 / src_max as f64;
+    let new_w = ((src_w as f64 * scale).floor() as u32).max(1);
+    let new_h = ((src_h as f64 * scale).floor() as u32).max(1);
+    log::warn!(
+        "{}",
+        crate::i18n::tr_with(
+            "gpu.image_too_large_scaled",
+            &[
+                ("src_w", src_w.to_string()),
+                ("src_h", src_h.to_string()),
+                ("max", max_texture_dimension.to_string()),
+                ("new_w", new_w.to_string()),
+                ("new_h", new_h.to_string())
+            ]
+        )
+    );
+    Some(resize_lanczos3(img, new_w, new_h))
+}
+
+/// 把等矩形图重新定向：新图的正前方对应原图 (yaw, pitch) 所指的方向，再绕视线转 roll（度，
+/// 见 projection::panorama_rotation）。只有 yaw 时按整像素水平循环平移，不重新采样；
+/// 否则逐像素在线性光下双线性插值（水平方向循环，垂直方向钳位）
+pub fn rotate_equirect(img: &RgbaImage, yaw_deg: f32, pitch_deg: f32, roll_deg: f32) -> RgbaImage {
+    let (w, h) = img.dimensions();
+    if pitch_deg == 0.0 && roll_deg == 0.0 {
+        let shift = ((yaw_deg / 360.0 * w as f32).round() as i64).rem_euclid(w as i64) as u32;
+        return crop_wrapped(img, shift, 0, w, h);
+    }
+
+    let rotation = crate::projection::panorama_rotation(
+        yaw_deg.to_radians(),
+        pitch_deg.to_radians(),
+        roll_deg.to_radians(),
+    );
+    let lut: Vec<f32> = (0..=255).map(|v| srgb_to_linear(v as f32 / 255.0)).collect();
+    let src = img.as_raw();
+    let texel = |x: i64, y: i64, c: usize| {
+        let x = x.rem_euclid(w as i64) as usize;
+        let y = y.clamp(0, h as i64 - 1) as usize;
+        src[(y * w as usize + x) * 4 + c]
+    };
+
+    let mut out = RgbaImage::new(w, h);
+    out.par_chunks_mut(4 * w as usize).enumerate().for_each(|(row, dst)| {
+        for (col, px) in dst.chunks_exact_mut(4).enumerate() {
+            let uv = glam::Vec2::new((col as f32 + 0.5) / w as f32, (row as f32 + 0.5) / h as f32);
+            let uv = crate::projection::direction_to_uv(rotation * crate::projection::uv_to_direction(uv));
+            // 纹素中心在 +0.5 处
+            let fx = uv.x * w as f32 - 0.5;
+            let fy = uv.y * h as f32 - 0.5;
+            let (x0, y0) = (fx.floor() as i64, fy.floor() as i64);
+            let (tx, ty) = (fx - fx.floor(), fy - fy.floor());
+            for (c, out) in px.iter_mut().enumerate() {
+                let sample = |x, y| {
+                    let v = texel(x, y, c);
+                    if c == 3 {
+                        v as f32 / 255.0
+                    } else {
+                        lut[v as usize]
+                    }
+                };
+                let top = sample(x0, y0) * (1.0 - tx) + sample(x0 + 1, y0) * tx;
+                let bottom = sample(x0, y0 + 1) * (1.0 - tx) + sample(x0 + 1, y0 + 1) * tx;
+                let v = top * (1.0 - ty) + bottom * ty;
+                let encoded = if c == 3 { v } else { linear_to_srgb(v.clamp(0.0, 1.0)) };
+                *out = (encoded * 255.0).round() as u8;
+            }
+        }
+    });
+    out
+}
+
+/// 从等矩形图中裁出 (x, y, w, h) 区域；x + w 超过右边缘时从左边缘继续（水平循环）
+pub fn crop_wrapped(img: &RgbaImage, x: u32, y: u32, w: u32, h: u32) -> RgbaImage {
+    let src_w = img.width() as usize;
+    let src = img.as_raw();
+    let mut out = RgbaImage::new(w, h);
+    out.par_chunks_mut(4 * w as usize).enumerate().for_each(|(row, dst)| {
+        let line = &src[(y as usize + row) * src_w * 4..][..src_w * 4];
+        let mut col = 0;
+        while col < w as usize {
+            let sx = (x as usize + col) % src_w;
+            let n = (src_w - sx).min(w as usize - col);
+            dst[col * 4..(col + n) * 4].copy_from_slice(&line[sx * 4..(sx + n) * 4]);
+            col += n;
+        }
+    });
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 与 image::imageops::resize(.., Lanczos3) 逐字节相同（模块开头的约定，缩小路径依赖它）
+    #[test]
+    fn lanczos3_matches_image_crate() {
+        // 渐变叠加确定性的伪随机噪声，尺寸为奇数，两个方向都缩小
+        let mut seed = 0x2545_f491_u32;
+        let img = RgbaImage::from_fn(97, 61, |x, y| {
+            let mut noise = || {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                (seed >> 24) as u8 / 4
+            };
+            Rgba([
+                (x * 2) as u8 + noise(),
+                (y * 3) as u8 + noise(),
+                ((x + y) % 256) as u8 / 2 + noise(),
+                192 + noise() / 2,
+            ])
+        });
+        for (w, h) in [(31, 23), (50, 7), (13, 60)] {
+            let expected = image::imageops::resize(&img, w, h, image::imageops::FilterType::Lanczos3);
+            assert_eq!(resize_lanczos3(&img, w, h).as_raw(), expected.as_raw(), "{w}x{h}");
+        }
+    }
+
+    /// 条幅全景不补齐画布，位置与补齐时相同（贴在画布底部）
+    #[test]
+    fn wide_strip_is_not_padded() {
+        let strip = RgbaImage::from_pixel(800, 100, Rgba([200, 100, 50, 255]));
+        let (img, coverage) = prepare_equirect(strip.clone(), None, u32::MAX, false);
+        assert_eq!(img.dimensions(), (800, 100));
+        assert_eq!(coverage, Some(Coverage { yaw: None, pitch: (45.0, 90.0), strip: true }));
+
+        let expanded = expand_strip(&img, (45.0, 90.0));
+        assert_eq!(expanded.dimensions(), (800, 400));
+        assert_eq!(expanded.get_pixel(0, 299).0, [0, 0, 0, 255]);
+        assert_eq!(expanded.get_pixel(0, 300).0, [200, 100, 50, 255]);
+    }
+
+    /// 加载前按补齐后的画布检查像素上限：GPano 局部全景按完整画布计，条幅全景按原尺寸计
+    #[test]
+    fn padded_dimensions_match_prepared_canvas() {
+        let crop = GPanoCrop { full_width: 4000, full_height: 2000, left: 1000, top: 500, width: 400, height: 300 };
+        assert_eq!(padded_dimensions(400, 300, Some(crop)), (4000, 2000));
+        assert_eq!(padded_dimensions(800, 100, None), (800, 100));
+
+        let partial = RgbaImage::from_pixel(400, 300, Rgba([1, 2, 3, 255]));
+        let (img, _) = prepare_equirect(partial, Some(crop), u32::MAX, false);
+        assert_eq!(img.dimensions(), padded_dimensions(400, 300, Some(crop)));
+    }
+
+    /// 每种 Orientation 下，转正后左上角的像素应来自原图的哪个角
+    #[test]
+    fn exif_orientation_covers_all_values() {
+        let (w, h) = (3, 2);
+        let img = RgbaImage::from_fn(w, h, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+        let expected = [
+            (1, (0, 0)),
+            (2, (2, 0)),
+            (3, (2, 1)),
+            (4, (0, 1)),
+            (5, (0, 0)),
+            (6, (0, 1)),
+            (7, (2, 1)),
+            (8, (2, 0)),
+        ];
+        for (orientation, (x, y)) in expected {
+            let out = apply_exif_orientation(img.clone(), orientation);
+            let size = if orientation >= 5 { (h, w) } else { (w, h) };
+            assert_eq!(out.dimensions(), size, "orientation {orientation}");
+            assert_eq!(out.get_pixel(0, 0).0[..2], [x, y], "orientation {orientation}");
+        }
+        // 5 与 7 是镜像：再检查右上角，区分转置前后的方向
+        assert_eq!(apply_exif_orientation(img.clone(), 5).get_pixel(1, 0).0[..2], [0, 1]);
+        assert_eq!(apply_exif_orientation(img, 7).get_pixel(1, 0).0[..2], [2, 0]);
+    }
+}
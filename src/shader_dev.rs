@@ -0,0 +1,80 @@
+// shader_dev.rs — 开发调试：运行时从磁盘读取 WGSL 并在修改后热重载
+//
+// 仅在命令行传入 `--shader-dev [path]`（或设置环境变量 PANORAMA_SHADER_DEV=path）时启用；
+// 默认路径为源码目录中的 src/shader_equirect.wgsl。正常运行时仍使用编译期内嵌的着色器。
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// 检查文件修改时间的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+const DEFAULT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/shader_equirect.wgsl");
+
+/// 从命令行 / 环境变量解析热重载的着色器路径；未启用时返回 None
+pub fn path_from_args() -> Option<PathBuf> {
+    let mut it = std::env::args().peekable();
+    while let Some(a) = it.next() {
+        if a == "--shader-dev" {
+            let path = it
+                .next_if(|v| !v.starts_with("--"))
+                .unwrap_or_else(|| DEFAULT_PATH.to_string());
+            return Some(PathBuf::from(path));
+        }
+    }
+
+    std::env::var("PANORAMA_SHADER_DEV")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .map(PathBuf::from)
+}
+
+pub struct ShaderWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+    missing: bool,
+}
+
+impl ShaderWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            // modified 为 None：首次 poll 必定返回源码，保证启动时就用磁盘上的版本
+            modified: None,
+            last_check: Instant::now().checked_sub(POLL_INTERVAL).unwrap_or_else(Instant::now),
+            missing: false,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 文件自上次读取后有修改时返回新的源码
+    pub fn poll(&mut self) -> Option<Result<String, String>> {
+        if self.last_check.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_check = Instant::now();
+
+        let modified = std::fs::metadata(&self.path).and_then(|m| m.modified());
+        match modified {
+            Ok(t) if self.modified == Some(t) => None,
+            Ok(t) => {
+                self.missing = false;
+                self.modified = Some(t);
+                Some(std::fs::read_to_string(&self.path).map_err(|e| e.to_string()))
+            }
+            Err(e) => {
+                // 编辑器保存时可能短暂删除文件：只在状态变化时报告一次
+                if self.missing {
+                    return None;
+                }
+                self.missing = true;
+                self.modified = None;
+                Some(Err(format!("{}: {}", self.path.display(), e)))
+            }
+        }
+    }
+}
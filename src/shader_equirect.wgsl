@@ -1,4 +1,11 @@
 // shader_equirect.wgsl - 支持多种投影的全景着色器
+//
+// 颜色管线（所有颜色运算都在线性光下进行）：
+// 1. 纹理格式为 Rgba8UnormSrgb，采样时硬件把 sRGB 编码解码为线性值，线性过滤也在线性空间完成
+// 2. 源图数据本身就是线性值时（linear_source），重新编码撤销这次解码，把原始数值当作线性光
+// 3. 画面调整在此之后、输出之前进行，输入输出都是线性值（曝光是线性倍率）；暗角放在所有调整之后，
+//    最后乘上新图片的淡入进度（load_fade）
+// 4. 输出：sRGB 格式的表面由硬件编码；表面不是 sRGB 格式时（encode_output）在 shader 中编码
 
 struct CameraUniform {
     aspect: f32,
@@ -6,15 +13,35 @@ struct CameraUniform {
     yaw: f32,
     pitch: f32,
     mode: u32, // 0=Rect, 1=Equidist, 2=Stereo, 3=Pannini, 4=Equirect, 5=Arch
-    // 填充对齐 (16 bytes align)
-    pad1: f32,
-    pad2: f32,
-    pad3: f32,
+    source: u32, // 0=等矩形纹理, 1=立方体贴图
+    seam_blend: f32, // 拼接缝羽化宽度（u 单位，0=关闭）
+    linear_source: u32, // 1=源图数据已是线性值，撤销采样时的 sRGB 解码
+
+    // 细节纹理在全图中的范围 (u0, v0, 宽, 高)，宽为 0 表示没有细节纹理
+    detail: vec4<f32>,
+    // 平面展开模式的平移量（弧度，x 向右、y 向下）
+    pan: vec2<f32>,
+    encode_output: u32, // 1=输出表面不是 sRGB 格式，需要在这里编码
+    vignette_strength: f32, // 暗角强度（画面四角的压暗比例，0=关闭）
+
+    exposure: f32, // 曝光倍率（2^EV），1=不调整
+    // 水平校正（弧度）：校正后的世界方向 -> 源图方向为 rotX(align_pitch) * rotZ(align_roll)
+    align_pitch: f32,
+    align_roll: f32,
+    load_fade: f32, // 新图片淡入的进度（0..1），1=完全显示
+
+    // 纹理在等矩形画布中的行范围 (v0, 高度)：条幅全景不补齐画布，纹理只含有效行；其余为 (0, 1)
+    tex_rows: vec2<f32>,
+    _pad1: f32,
+    _pad2: f32,
 };
 
 @group(0) @binding(0) var<uniform> camera: CameraUniform;
 @group(0) @binding(1) var t_diffuse: texture_2d<f32>;
 @group(0) @binding(2) var s_diffuse: sampler;
+@group(0) @binding(3) var t_cube: texture_cube<f32>;
+// 超大图片放大时，从全分辨率原图裁出的可见区域（t_diffuse 是缩小后的整图）
+@group(0) @binding(4) var t_detail: texture_2d<f32>;
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
@@ -83,8 +110,102 @@ fn rotY(a: f32) -> mat3x3<f32> {
     );
 }
 
+fn rotZ(a: f32) -> mat3x3<f32> {
+    let c = cos(a);
+    let s = sin(a);
+    return mat3x3<f32>(
+        vec3<f32>(c, s, 0.0),
+        vec3<f32>(-s, c, 0.0),
+        vec3<f32>(0.0, 0.0, 1.0)
+    );
+}
+
+fn align_dir(world_dir: vec3<f32>) -> vec3<f32> {
+    return rotX(camera.align_pitch) * (rotZ(camera.align_roll) * world_dir);
+}
+
+// 立方体贴图采样：层顺序 +X,-X,+Y,-Y,+Z,-Z，约定“前”(+Z 层) 为相机的 -Z 方向，
+// 因此翻转 z（立方体贴图采样坐标是左手系）
+fn sample_cube(world_dir: vec3<f32>) -> vec4<f32> {
+    return textureSample(t_cube, s_diffuse, vec3<f32>(world_dir.x, world_dir.y, -world_dir.z));
+}
+
+// 采样等矩形图：落在细节纹理范围内（留出一个纹素的边，避免线性过滤取到范围外）时用细节纹理，
+// 否则用缩小后的整图。两张纹理都先采样，保证 textureSample 位于 uniform control flow 中
+fn sample_source(uv: vec2<f32>) -> vec4<f32> {
+    let base = textureSample(t_diffuse, s_diffuse, uv);
+    let size = max(camera.detail.zw, vec2<f32>(1e-6));
+    let local = vec2<f32>(fract(uv.x - camera.detail.x), uv.y - camera.detail.y) / size;
+    let detail = textureSample(t_detail, s_diffuse, local);
+    let inset = 1.0 / vec2<f32>(textureDimensions(t_detail));
+    let inside = all(local >= inset) && all(local <= vec2<f32>(1.0) - inset);
+    if (camera.detail.z > 0.0 && inside) {
+        return detail;
+    }
+    return base;
+}
+
+// 等矩形纹理采样；seam_blend > 0 时在 u=0/1 接缝两侧做镜像羽化：
+// 距接缝 d 处混入接缝另一侧对称位置的像素，权重从接缝处的 0.5 线性降到 d=seam_blend 处的 0，
+// 这样接缝两边在 d=0 处取值相同，拼接错位被平滑过渡掉（代价是接缝附近略微变软）
+fn sample_equirect(uv: vec2<f32>) -> vec4<f32> {
+    let u = fract(uv.x);
+    let d = min(u, 1.0 - u);
+    // 画布 v -> 纹理 v；纹理行以外（条幅全景上下没有内容的部分）为黑色
+    let v = (uv.y - camera.tex_rows.x) / camera.tex_rows.y;
+    let color = sample_source(vec2<f32>(u, v));
+    let mirrored = sample_source(vec2<f32>(1.0 - u, v));
+    var w = 0.0;
+    if (camera.seam_blend > 0.0) {
+        w = 0.5 * clamp(1.0 - d / camera.seam_blend, 0.0, 1.0);
+    }
+    if (v < 0.0 || v > 1.0) {
+        return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+    return mix(color, mirrored, w);
+}
+
+// 等矩形 UV -> 世界方向（与下面 fs_main 中方向 -> UV 的映射互逆）
+fn uv_to_dir(uv: vec2<f32>) -> vec3<f32> {
+    let phi = (uv.x - 0.75) * 2.0 * PI;
+    let theta = (0.5 - uv.y) * PI;
+    return vec3<f32>(cos(theta) * cos(phi), sin(theta), cos(theta) * sin(phi));
+}
+
+// 世界方向 -> 等矩形 UV（与 fs_main 末尾的映射相同）
+fn dir_to_uv(dir: vec3<f32>) -> vec2<f32> {
+    let u = fract(atan2(dir.z, dir.x) / (2.0 * PI) + 0.75);
+    let v = 0.5 - asin(clamp(dir.y, -1.0, 1.0)) / PI;
+    return vec2<f32>(u, v);
+}
+
+// 线性值 -> sRGB 编码（IEC 61966-2-1 分段曲线）
+fn srgb_encode(c: vec3<f32>) -> vec3<f32> {
+    let x = max(c, vec3<f32>(0.0));
+    return select(1.055 * pow(x, vec3<f32>(1.0 / 2.4)) - 0.055, x * 12.92, x <= vec3<f32>(0.0031308));
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = scene_color(in);
+    // 见文件开头的颜色管线说明
+    if (camera.linear_source != 0u) {
+        color = vec4<f32>(srgb_encode(color.rgb), color.a);
+    }
+    color = vec4<f32>(color.rgb * camera.exposure, color.a);
+    // 暗角：按到画面中心的距离（四角为 1）压暗，中心约一半的区域不受影响
+    let corner = length(vec2<f32>(camera.aspect, 1.0));
+    let r = length(vec2<f32>(in.uv.x * camera.aspect, in.uv.y)) / corner;
+    color = vec4<f32>(color.rgb * (1.0 - camera.vignette_strength * smoothstep(0.45, 1.0, r)), color.a);
+    color = vec4<f32>(color.rgb * camera.load_fade, color.a);
+    if (camera.encode_output != 0u) {
+        color = vec4<f32>(srgb_encode(color.rgb), color.a);
+    }
+    return color;
+}
+
+// 当前像素对应的源图颜色（线性值，经过硬件 sRGB 解码）
+fn scene_color(in: VertexOutput) -> vec4<f32> {
     // 1. 归一化屏幕坐标 (-1..1) 并应用 Aspect Ratio
     let p = vec2<f32>(in.uv.x * camera.aspect, in.uv.y);
     
@@ -144,18 +265,29 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         // Then normalize.
         dir = normalize(vec3<f32>(sin(theta), h, -cos(theta)));
     } else if (camera.mode == 4u) { // Equirectangular (Flat View)
-        // Simply map UV to texture directly.
-        // u = in.uv.x * 0.5 + 0.5
-        // v = in.uv.y * 0.5 + 0.5
-        // We need to bypass the rotation logic or handle it differently.
-        // Let's just return sample here.
-        let u = in.uv.x * 0.5 + 0.5; // -1..1 -> 0..1
-        let v = 1.0 - (in.uv.y * 0.5 + 0.5); // Y Up -> V Down
-        // With pan/zoom:
-        // shift u by yaw, scale by fov.
-        // Simple implementation:
-        let u_pan = fract(u - camera.yaw / (2.0 * PI) + 1.0);
-        return textureSample(t_diffuse, s_diffuse, vec2<f32>(u_pan, v));
+        // 平面展开（图片平移查看器）：
+        // - yaw/pitch 平移可见窗口的中心（与 3D 模式方向一致：yaw 增大向右，pitch 增大向下），
+        //   中键拖动的 pan 叠加在上面
+        // - fov 为垂直方向可见角度（180° = 整幅高度），水平按 2:1 保持像素等比
+        // - 水平方向由 Repeat sampler 自动 wrap；垂直方向超出 [0,1] 显示黑色
+        let span_v = camera.fov_rad / PI;
+        let span_u = span_v * camera.aspect * 0.5;
+        let u = 0.5 + (camera.yaw + camera.pan.x) / (2.0 * PI) + in.uv.x * span_u * 0.5;
+        let v = 0.5 + (camera.pitch + camera.pan.y) / PI - in.uv.y * span_v * 0.5;
+        // 有水平校正时展开的是校正后的全景：经方向转回源图坐标
+        var src_uv = vec2<f32>(u, clamp(v, 0.0, 1.0));
+        if (camera.align_pitch != 0.0 || camera.align_roll != 0.0) {
+            src_uv = dir_to_uv(align_dir(uv_to_dir(src_uv)));
+        }
+        // 先采样再判断，保证 textureSample 位于 uniform control flow 中
+        var color = sample_equirect(src_uv);
+        if (camera.source == 1u) {
+            color = sample_cube(align_dir(uv_to_dir(vec2<f32>(u, clamp(v, 0.0, 1.0)))));
+        }
+        if (v < 0.0 || v > 1.0) {
+            return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+        }
+        return color;
     }
     
     // 3. Apply Rotation (Yaw, Pitch)
@@ -190,7 +322,15 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         // WorldDir = RotY(yaw) * RotX(pitch) * LocalDir
         world_dir = rotY(camera.yaw) * (rotX(camera.pitch) * dir);
     }
+
+    // 水平校正（虚拟三脚架）：把校正后的世界方向转回源图中的方向
+    world_dir = align_dir(world_dir);
     
+    // 六面体输入：直接按方向采样立方体贴图，无需转换为等矩形
+    if (camera.source == 1u) {
+        return sample_cube(world_dir);
+    }
+
     // 4. Convert World Direction to Equirectangular UV
     // Standard mapping:
     // +Z = Back (u=1.0), -Z = Front (u=0.5)
@@ -220,5 +360,5 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // v = 0.5 - theta / PI.
     let v = 0.5 - theta / PI;
     
-    return textureSample(t_diffuse, s_diffuse, vec2<f32>(u, v));
+    return sample_equirect(vec2<f32>(u, v));
 }
@@ -0,0 +1,89 @@
+// shader_mesh.wgsl - 网格球体渲染（备用路径）：真实顶点/索引缓冲 + 透视相机
+//
+// 只支持标准透视；用于与 shader_equirect.wgsl 的光线投射结果对照，
+// 或在片元着色器较慢的老旧 GPU 上使用。绑定组 0 与主着色器相同。
+
+struct CameraUniform {
+    aspect: f32,
+    fov_rad: f32,
+    yaw: f32,
+    pitch: f32,
+    mode: u32,
+    source: u32, // 0=等矩形纹理, 1=立方体贴图
+    seam_blend: f32, // 网格路径作为对照参考，不做接缝羽化
+    linear_source: u32,
+    detail: vec4<f32>, // 网格路径只采样缩小后的整图，不使用细节纹理
+    pan: vec2<f32>, // 只用于平面展开模式
+    encode_output: u32,
+    vignette_strength: f32, // 网格路径作为对照参考，不画暗角
+    exposure: f32, // 曝光在两条路径上都生效
+    align_pitch: f32, // 水平校正已经包含在 view_proj 中
+    align_roll: f32,
+    load_fade: f32, // 新图片淡入在两条路径上都生效
+    tex_rows: vec2<f32>, // 条幅全景的纹理行范围，与 shader_equirect.wgsl 相同
+    _pad1: f32,
+    _pad2: f32,
+};
+
+struct MeshUniform {
+    view_proj: mat4x4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> camera: CameraUniform;
+@group(0) @binding(1) var t_diffuse: texture_2d<f32>;
+@group(0) @binding(2) var s_diffuse: sampler;
+@group(0) @binding(3) var t_cube: texture_cube<f32>;
+@group(1) @binding(0) var<uniform> mesh: MeshUniform;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) world_pos: vec3<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = mesh.view_proj * vec4<f32>(in.position, 1.0);
+    // mesh.rs 的 UV 沿用 Java 版约定 (1 - phi/2π, 1 - theta/π)，
+    // 换算到主着色器的等矩形约定 u = phi/2π + 0.75, v = theta/π；
+    // u 可能为负，由 Repeat sampler 处理，保证三角形内部插值连续
+    out.uv = vec2<f32>(0.75 - in.uv.x, 1.0 - in.uv.y);
+    out.world_pos = in.position;
+    return out;
+}
+
+// 线性值 -> sRGB 编码，颜色管线与 shader_equirect.wgsl 相同
+fn srgb_encode(c: vec3<f32>) -> vec3<f32> {
+    let x = max(c, vec3<f32>(0.0));
+    return select(1.055 * pow(x, vec3<f32>(1.0 / 2.4)) - 0.055, x * 12.92, x <= vec3<f32>(0.0031308));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // 两种纹理都先采样，保证 textureSample 位于 uniform control flow 中
+    let d = normalize(in.world_pos);
+    let v = (in.uv.y - camera.tex_rows.x) / camera.tex_rows.y;
+    let equirect = textureSample(t_diffuse, s_diffuse, vec2<f32>(in.uv.x, v));
+    let cube = textureSample(t_cube, s_diffuse, vec3<f32>(d.x, d.y, -d.z));
+    var color = equirect;
+    if (v < 0.0 || v > 1.0) {
+        color = vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+    if (camera.source == 1u) {
+        color = cube;
+    }
+    if (camera.linear_source != 0u) {
+        color = vec4<f32>(srgb_encode(color.rgb), color.a);
+    }
+    color = vec4<f32>(color.rgb * camera.exposure * camera.load_fade, color.a);
+    if (camera.encode_output != 0u) {
+        color = vec4<f32>(srgb_encode(color.rgb), color.a);
+    }
+    return color;
+}
@@ -0,0 +1,55 @@
+// shortcuts.rs — 快捷键一览，帮助窗口（F1）直接由这张表生成
+//
+// 在 app.rs 中新增或修改快捷键时，请同步修改这里；可在配置中修改的按键写成
+// `{动作名}` 模板，显示时换成当前绑定（见 keybindings.rs）。
+
+/// 触发方式
+pub enum Input {
+    /// 键盘按键模板（显示时不翻译）
+    Key(&'static str),
+    /// 鼠标操作（i18n key）
+    Mouse(&'static str),
+}
+
+pub struct Shortcut {
+    pub input: Input,
+    /// 说明文字的 i18n key
+    pub action: &'static str,
+}
+
+impl Shortcut {
+    pub fn input_label(&self, bindings: &crate::keybindings::Keybindings) -> String {
+        match self.input {
+            Input::Key(keys) => bindings.describe(keys),
+            Input::Mouse(key) => crate::i18n::tr(key),
+        }
+    }
+}
+
+const fn key(keys: &'static str, action: &'static str) -> Shortcut {
+    Shortcut { input: Input::Key(keys), action }
+}
+
+const fn mouse(input: &'static str, action: &'static str) -> Shortcut {
+    Shortcut { input: Input::Mouse(input), action }
+}
+
+pub const SHORTCUTS: &[Shortcut] = &[
+    key("{open}", "shortcut.open"),
+    key("{previous} / {next}", "shortcut.prev_next"),
+    key("Ctrl+{copy_view}", "shortcut.copy_view"),
+    mouse("shortcut.input.drag", "shortcut.rotate"),
+    mouse("shortcut.input.middle_drag", "shortcut.pan_flat"),
+    mouse("shortcut.input.wheel", "shortcut.zoom"),
+    mouse("shortcut.input.wheel_ctrl", "shortcut.zoom_fine"),
+    mouse("shortcut.input.wheel_shift", "shortcut.zoom_coarse"),
+    key("{north} / {east} / {south} / {west}", "shortcut.compass"),
+    key("{level_horizon}", "shortcut.level"),
+    key("{reset}", "shortcut.reset"),
+    key("{fit}", "shortcut.fit"),
+    key("{focal_shorter} / {focal_longer}", "shortcut.focal_presets"),
+    key("{cycle_projection} / Shift+{cycle_projection}", "shortcut.cycle_projection"),
+    key("{toggle_animation}", "shortcut.animation"),
+    key("{fullscreen}", "shortcut.fullscreen"),
+    key("{help}", "shortcut.help"),
+];
@@ -0,0 +1,77 @@
+// sidecar.rs — 与图片放在一起的初始视角文件（`photo.jpg.view.json`）
+//
+// 用于分发带推荐起始视角的全景：后台线程解码成功后读取，主线程在纹理就位后应用。
+// 所有字段都可省略；文件不存在时静默跳过，格式错误时输出提示后忽略。
+//   { "yaw": 30, "pitch": -10, "fov": 70, "projection": "pannini",
+//     "markers": [{ "yaw": 90, "pitch": 0, "text": "Kitchen" }] }
+// projection 的取值与 --projection 相同；yaw/pitch/fov 为度，含义与命令行参数相同。
+
+use crate::markers::Marker;
+use crate::panorama::{PanoramaViewer3D, ProjectionMode};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ViewSidecar {
+    pub yaw: Option<f32>,
+    pub pitch: Option<f32>,
+    pub fov: Option<f32>,
+    /// 投影的命令行名称（见 ProjectionMode::cli_name）
+    pub projection: Option<String>,
+    /// 图片还没有保存过标注时作为初始标注
+    pub markers: Vec<Marker>,
+    /// 对应图片的标注键（markers::image_key），读取时填入
+    #[serde(skip)]
+    pub image_key: String,
+}
+
+/// `photo.jpg` -> `photo.jpg.view.json`
+pub fn sidecar_path(image: &Path) -> PathBuf {
+    let mut name = image.as_os_str().to_os_string();
+    name.push(".view.json");
+    PathBuf::from(name)
+}
+
+/// 读取图片旁的视角文件；不存在或无法解析时返回 None
+pub fn load(image: &Path) -> Option<ViewSidecar> {
+    let path = sidecar_path(image);
+    let text = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<ViewSidecar>(&text) {
+        Ok(sidecar) => Some(ViewSidecar { image_key: crate::markers::image_key(image), ..sidecar }),
+        Err(e) => {
+            log::warn!(
+                "{}",
+                crate::i18n::tr_with(
+                    "log.sidecar_invalid",
+                    &[("path", path.display().to_string()), ("err", e.to_string())]
+                )
+            );
+            None
+        }
+    }
+}
+
+impl ViewSidecar {
+    pub fn projection_mode(&self) -> Option<ProjectionMode> {
+        self.projection.as_deref().and_then(ProjectionMode::from_cli_name)
+    }
+
+    /// 按与命令行参数相同的顺序应用：先投影（FOV 回到该模式默认值），再 FOV、yaw、pitch；
+    /// allow_projection 为 false 时（网格路径只支持透视）不切换投影
+    pub fn apply(&self, viewer: &mut PanoramaViewer3D, allow_projection: bool) {
+        if let Some(mode) = self.projection_mode().filter(|_| allow_projection) {
+            viewer.projection_mode = mode;
+            viewer.fov = viewer.default_fov();
+        }
+        if let Some(fov) = self.fov {
+            viewer.fov = viewer.clamp_fov(fov);
+        }
+        if let Some(yaw) = self.yaw {
+            viewer.yaw = yaw;
+        }
+        if let Some(pitch) = self.pitch {
+            viewer.pitch = viewer.constrain_pitch(pitch);
+        }
+    }
+}
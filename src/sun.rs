@@ -0,0 +1,59 @@
+// sun.rs — 由拍摄时间 + GPS 估算太阳方位角/高度角
+//
+// 采用常见的低精度太阳星历（误差约 0.01°~0.1°），对“看光线方向”已足够。
+
+use crate::metadata::{ImageMetadata, UtcDateTime};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunPosition {
+    /// 方位角（度，正北为 0，顺时针）
+    pub azimuth_deg: f64,
+    /// 高度角（度，地平线为 0，向上为正）
+    pub altitude_deg: f64,
+    /// 相对图像中心的 yaw（度），可直接与 viewer.yaw 比较
+    pub yaw_deg: f64,
+}
+
+impl SunPosition {
+    /// 时间、GPS、朝向三者齐全时才能在画面中定位太阳
+    pub fn from_metadata(meta: &ImageMetadata) -> Option<Self> {
+        let time = meta.capture_time?;
+        let (lat, lon) = meta.gps?;
+        let heading = meta.heading_deg?;
+
+        let (azimuth_deg, altitude_deg) = solar_position(&time, lat, lon);
+        Some(Self {
+            azimuth_deg,
+            altitude_deg,
+            yaw_deg: (azimuth_deg - heading).rem_euclid(360.0),
+        })
+    }
+}
+
+/// 返回 (方位角, 高度角)，单位度
+pub fn solar_position(time: &UtcDateTime, lat_deg: f64, lon_deg: f64) -> (f64, f64) {
+    // 自 J2000.0 起的天数
+    let n = time.unix_days() + 2440587.5 - 2451545.0;
+
+    // 太阳平黄经 / 平近点角 / 黄经
+    let l = (280.460 + 0.9856474 * n).rem_euclid(360.0);
+    let g = (357.528 + 0.9856003 * n).rem_euclid(360.0).to_radians();
+    let lambda = (l + 1.915 * g.sin() + 0.020 * (2.0 * g).sin()).to_radians();
+    let epsilon = (23.439 - 0.0000004 * n).to_radians();
+
+    // 赤经 / 赤纬
+    let alpha = (epsilon.cos() * lambda.sin()).atan2(lambda.cos());
+    let delta = (epsilon.sin() * lambda.sin()).asin();
+
+    // 格林尼治平恒星时 -> 本地时角
+    let gmst_hours = (18.697374558 + 24.06570982441908 * n).rem_euclid(24.0);
+    let lst = (gmst_hours * 15.0 + lon_deg).to_radians();
+    let h = lst - alpha;
+
+    let phi = lat_deg.to_radians();
+    let altitude = (phi.sin() * delta.sin() + phi.cos() * delta.cos() * h.cos()).asin();
+    let azimuth = (-delta.cos() * h.sin())
+        .atan2(delta.sin() * phi.cos() - delta.cos() * h.cos() * phi.sin());
+
+    (azimuth.to_degrees().rem_euclid(360.0), altitude.to_degrees())
+}
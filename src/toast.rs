@@ -0,0 +1,64 @@
+// toast.rs — 右下角的短暂提示（操作完成 / 出错）
+
+use std::time::{Duration, Instant};
+
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToastKind {
+    Info,
+    Error,
+}
+
+struct Toast {
+    text: String,
+    kind: ToastKind,
+    created: Instant,
+}
+
+#[derive(Default)]
+pub struct Toasts {
+    items: Vec<Toast>,
+}
+
+impl Toasts {
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(text.into(), ToastKind::Info);
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.push(text.into(), ToastKind::Error);
+    }
+
+    fn push(&mut self, text: String, kind: ToastKind) {
+        self.items.push(Toast {
+            text,
+            kind,
+            created: Instant::now(),
+        });
+    }
+
+    /// 绘制仍在显示期内的提示，过期的自动移除
+    pub fn show(&mut self, ctx: &egui::Context) {
+        self.items.retain(|t| t.created.elapsed() < TOAST_DURATION);
+        if self.items.is_empty() {
+            return;
+        }
+
+        egui::Area::new("toasts")
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -36.0))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                for toast in &self.items {
+                    let color = match toast.kind {
+                        ToastKind::Info => egui::Color32::WHITE,
+                        ToastKind::Error => egui::Color32::LIGHT_RED,
+                    };
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.label(egui::RichText::new(&toast.text).color(color));
+                    });
+                }
+            });
+    }
+}